@@ -1,5 +1,14 @@
+use std::cell::{Ref, RefMut};
+
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::system_program;
+use anchor_spl::metadata::{
+    create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
+    Metadata as TokenMetadata,
+};
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use bytemuck::Zeroable;
 
 declare_id!("2qdp2bKXQHhRiD1kPS22Zyx3dxuevXkiRgvWKghHSGzx");
 
@@ -13,24 +22,36 @@ const fn parse_u64_from_str(s: &str) -> u64 {
         if b >= b'0' && b <= b'9' {
             result = result * 10 + (b - b'0') as u64;
         } else {
-            // Invalid character, return default of 1
-            return 1;
+            // Invalid character: fail the build instead of silently picking a scale
+            panic!("PRICE_SCALE must be a decimal integer");
         }
         i += 1;
     }
     result
 }
 
-// Price scale for fractional prices: defaults to 1 if PRICE_SCALE env var is not set
+// Price scale for fractional prices: defaults to 100 (prices in hundredths of collateral)
+// if PRICE_SCALE env var is not set
 // Can be set at compile time via environment variable: PRICE_SCALE=1000000000 cargo build
-// Example: price = 1_500_000_000 represents 1.5, price = 500_000_000 represents 0.5
+// Example: price = 1_000_000_000 represents 1, price = 500_000_000 represents 0.5
+// An outcome token pays at most 1 unit of collateral, so limit prices must be in (0, PRICE_SCALE]
 pub const PRICE_SCALE: u64 = {
     const ENV_STR: Option<&str> = option_env!("PRICE_SCALE");
     match ENV_STR {
         Some(s) => parse_u64_from_str(s),
-        None => 1,
+        None => 100,
     }
 };
+const _: () = assert!(PRICE_SCALE > 0, "PRICE_SCALE must be positive");
+
+// Denominator for values expressed in basis points
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+// After this long, a resolved market can be closed even if tokens remain unredeemed
+pub const CLOSE_GRACE_PERIOD_SECS: i64 = 180 * 24 * 60 * 60;
+
+// Discount (basis points) off a positive maker fee for each designated market maker fee tier
+pub const DMM_FEE_TIER_DISCOUNTS_BPS: [u16; 5] = [0, 2_500, 5_000, 7_500, 10_000];
 
 // --- Instruction Data Structs ---
 
@@ -38,6 +59,7 @@ pub const PRICE_SCALE: u64 = {
 pub struct MarketBuyParams {
     pub quantity: u64,
     pub want_yes: bool,
+    pub self_trade: SelfTradePrevention,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -46,6 +68,115 @@ pub struct BuyExactParams {
     pub max_price: u64,
     pub quantity: u64,
     pub want_yes: bool,
+    pub self_trade: SelfTradePrevention,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MarketSellParams {
+    pub quantity: u64,
+    pub sell_yes: bool,
+    pub self_trade: SelfTradePrevention,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SellExactParams {
+    /// Minimum price per unit as scaled integer: min_price = actual_price * PRICE_SCALE
+    pub min_price: u64,
+    pub quantity: u64,
+    pub sell_yes: bool,
+    pub self_trade: SelfTradePrevention,
+}
+
+/// How long a limit order may stay on the book.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// Good till cancelled: rests until filled or cancelled
+    Gtc,
+    /// Good till time: rests until `expiry_ts`, after which it can no longer be filled
+    /// and anyone may purge it with `purge_expired_order`
+    Gtt { expiry_ts: i64 },
+    /// Immediate or cancel: takes what it can at the limit price or better, the rest is dropped
+    Ioc,
+    /// Fill or kill: takes the whole quantity at the limit price or better, or fails
+    Fok,
+}
+
+/// What a post-only limit order does if it would take liquidity.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostOnly {
+    /// Fail with OrderWouldCross
+    Reject,
+    /// Rest one tick behind the best opposite order instead
+    Slide,
+}
+
+/// What happens when a taker would fill against one of its own resting orders.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Cancel the resting order, returning its escrow, and keep matching
+    CancelOldest,
+    /// Cancel the rest of the incoming order; an all-or-nothing order fails with SelfTrade
+    CancelNewest,
+    /// Reduce both orders by their overlap without trading and keep matching
+    DecrementBoth,
+}
+
+/// When minting and trading are allowed: from `open_ts` until (excluding) `close_ts`.
+/// Trading must close by the market's expiry, so nobody trades on a market that can be resolved.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradingWindow {
+    pub open_ts: i64,
+    /// Typically the kickoff, so pre-game markets close without an operator halting them
+    pub close_ts: i64,
+}
+
+/// Who settles a market and how; see `propose_resolution`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolutionParams {
+    /// Key allowed to propose the outcome
+    pub resolver: Pubkey,
+    /// Key that rules on disputed proposals
+    pub escalation_resolver: Pubkey,
+    /// Seconds after a proposal during which it can be disputed
+    pub challenge_period_secs: i64,
+    /// Collateral posted by the proposer and by a disputer
+    pub bond: u64,
+    /// Resolution committee; when non-empty the market resolves by `vote_resolution` instead
+    pub committee: Vec<Pubkey>,
+    /// Number of committee members who must agree on an outcome
+    pub committee_threshold: u8,
+}
+
+/// Description of the event a market is on; see `MarketMetadata`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarketMetadataParams {
+    pub title: String,
+    pub resolution_criteria: String,
+    pub resolution_criteria_hash: [u8; 32],
+    pub category: String,
+    pub event_id: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub kickoff_ts: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DmmProgramParams {
+    /// Widest allowed spread between the maker's best YES and NO asks,
+    /// measured as yes_ask + no_ask - PRICE_SCALE (scaled like prices)
+    pub max_spread: u64,
+    /// Smallest quantity an ask must have to count towards the obligation
+    pub min_size: u64,
+    /// Share of samples (in basis points) that must be compliant to keep benefits
+    pub min_uptime_bps: u16,
+    /// Number of samples required before benefits can be granted
+    pub min_samples: u64,
+    /// Length in seconds of one sampling period; must be positive
+    pub sample_interval_secs: i64,
+    /// Fee tier granted to compliant makers: an index into `DMM_FEE_TIER_DISCOUNTS_BPS`
+    pub fee_tier: u8,
+    /// Share of the taker fee (in basis points) rebated to compliant makers on their fills
+    pub rebate_share_bps: u16,
 }
 
 /// NFL Blockchain program.
@@ -53,30 +184,109 @@ pub struct BuyExactParams {
 pub mod nfl_blockchain {
     use super::*;
 
-    /// Create a new binary market.
-    pub fn create_market(ctx: Context<CreateMarket>, expiry_ts: i64) -> Result<()> {
-        require!(expiry_ts > 0, NflError::InvalidExpiry);
+    /// Create a new binary market and its metadata account.
+    /// The market, its mints and its vault are PDAs of (authority, metadata.event_id).
+    /// `resolution` names the keys that settle the market, which may differ from the authority.
+    /// Minting and trading are only allowed inside `trading_window`, e.g. up to kickoff.
+    pub fn create_market(
+        ctx: Context<CreateMarket>,
+        metadata: MarketMetadataParams,
+        expiry_ts: i64,
+        trading_window: TradingWindow,
+        resolution: ResolutionParams,
+    ) -> Result<()> {
+        init_market(ctx, expiry_ts, trading_window, MarketKind::Binary, metadata, resolution)
+    }
 
-        let market = &mut ctx.accounts.market;
+    /// Create a new scalar market on a continuous quantity such as final margin or total points.
+    /// YES and NO act as LONG and SHORT: both are minted as pairs with `mint_pairs`, and at
+    /// resolution LONG is paid linearly by where the final value falls in [lower, upper].
+    pub fn create_scalar_market(
+        ctx: Context<CreateMarket>,
+        metadata: MarketMetadataParams,
+        expiry_ts: i64,
+        trading_window: TradingWindow,
+        resolution: ResolutionParams,
+        lower: i64,
+        upper: i64,
+    ) -> Result<()> {
+        require!(lower < upper, NflError::InvalidScalarRange);
+        init_market(ctx, expiry_ts, trading_window, MarketKind::Scalar { lower, upper }, metadata, resolution)
+    }
 
-        market.authority = ctx.accounts.authority.key();
-        market.base_mint = ctx.accounts.base_mint.key();
-        market.yes_mint = ctx.accounts.yes_mint.key();
-        market.no_mint = ctx.accounts.no_mint.key();
-        market.vault = ctx.accounts.vault.key();
-        market.expiry_ts = expiry_ts;
-        market.status = MarketStatus::Open;
-        market.outcome = Outcome::Pending;
-        market.market_authority_bump = ctx.bumps.market_authority;
+    /// Update the description of a market.
+    /// Only allowed before its trading window opens, so traders never see the terms change
+    /// under them. The event id cannot change, since the market's address is derived from it.
+    pub fn update_market_metadata(ctx: Context<UpdateMarketMetadata>, metadata: MarketMetadataParams) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < ctx.accounts.market.trading_open_ts, NflError::MetadataLocked);
+        require!(metadata.event_id == ctx.accounts.metadata.event_id, NflError::InvalidEventId);
+
+        ctx.accounts.metadata.set(metadata)?;
+
+        msg!("Metadata updated for market {}", ctx.accounts.market.key());
+        Ok(())
+    }
+
+    /// Create Metaplex token metadata for the YES and NO mints so wallets can display them.
+    /// Names are built from the market's metadata, e.g. "KC@BUF YES" (LONG/SHORT for scalar markets).
+    /// Signed by the `market_auth` PDA, which is the mint authority and stays the update authority.
+    pub fn create_outcome_token_metadata(ctx: Context<CreateOutcomeTokenMetadata>, uri: String) -> Result<()> {
+        let market = &ctx.accounts.market;
+        let event = &ctx.accounts.metadata;
+
+        let (yes_label, no_label) = match market.kind {
+            MarketKind::Binary => ("YES", "NO"),
+            MarketKind::Scalar { .. } => ("LONG", "SHORT"),
+        };
+        let matchup = format!("{}@{}", event.away_team, event.home_team);
+
+        // PDA seeds for market_authority
+        let market_key = market.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"market_auth",
+            market_key.as_ref(),
+            &[market.market_authority_bump],
+        ];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        for (mint, token_metadata, label) in [
+            (&ctx.accounts.yes_mint, &ctx.accounts.yes_token_metadata, yes_label),
+            (&ctx.accounts.no_mint, &ctx.accounts.no_token_metadata, no_label),
+        ] {
+            let cpi_accounts = CreateMetadataAccountsV3 {
+                metadata: token_metadata.to_account_info(),
+                mint: mint.to_account_info(),
+                mint_authority: ctx.accounts.market_authority.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                update_authority: ctx.accounts.market_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            let data = DataV2 {
+                name: format!("{} {}", matchup, label),
+                symbol: label.to_string(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            };
+            create_metadata_accounts_v3(cpi_ctx, data, true, true, None)?;
+        }
 
         msg!(
-            "Market created: market={}, base_mint={}, yes_mint={}, no_mint={}, vault={}, expiry_ts={}",
+            "Token metadata created for market {}: \"{} {}\" / \"{} {}\"",
             market.key(),
-            market.base_mint,
-            market.yes_mint,
-            market.no_mint,
-            market.vault,
-            expiry_ts
+            matchup,
+            yes_label,
+            matchup,
+            no_label
         );
 
         Ok(())
@@ -87,7 +297,7 @@ pub mod nfl_blockchain {
         require!(amount > 0, NflError::InvalidAmount);
 
         let market = &ctx.accounts.market;
-        require!(market.status == MarketStatus::Open, NflError::MarketNotOpen);
+        market.require_trading_open()?;
 
         // On-chain keys must match Market config
         require_keys_eq!(
@@ -162,83 +372,49 @@ pub mod nfl_blockchain {
             token::mint_to(cpi_ctx, amount)?;
         }
 
-        msg!(
-            "Minted {} YES/NO pairs for user {} in market {}",
+        emit_cpi!(PairsMinted {
+            market: market.key(),
+            user: ctx.accounts.user.key(),
             amount,
-            ctx.accounts.user.key(),
-            market.key()
-        );
+        });
 
         Ok(())
     }
 
-    /// Resolve a market to a final outcome.
-    pub fn resolve_market(ctx: Context<ResolveMarket>, outcome: Outcome) -> Result<()> {
-        let market = &mut ctx.accounts.market;
+    /// Merge YES/NO pairs back into collateral.
+    /// Burns `amount` of both outcome tokens and releases the same amount from the vault.
+    /// Allowed until the market is resolved.
+    pub fn merge_pairs(ctx: Context<MergePairs>, amount: u64) -> Result<()> {
+        require!(amount > 0, NflError::InvalidAmount);
 
+        let market = &ctx.accounts.market;
         require!(
             market.status != MarketStatus::Resolved,
             NflError::MarketAlreadyResolved
         );
 
-        let now = Clock::get()?.unix_timestamp;
-        require!(now >= market.expiry_ts, NflError::MarketNotExpired);
-
-        require!(
-            matches!(outcome, Outcome::Yes | Outcome::No | Outcome::Invalid),
-            NflError::InvalidResolutionOutcome
-        );
-
-        market.status = MarketStatus::Resolved;
-        market.outcome = outcome;
-
-        msg!(
-            "Market {} resolved to {:?} at unix_ts={}",
-            market.key(),
-            outcome,
-            now
-        );
-
-        Ok(())
-    }
-
-    /// Redeem winning YES/NO tokens for collateral.
-    pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
-        let market = &ctx.accounts.market;
-
-        require!(
-            market.status == MarketStatus::Resolved,
-            NflError::MarketNotResolved
-        );
-
-        let (winner_mint_ai, winner_ata_ai, winner_amount) = match market.outcome {
-            Outcome::Yes => (
-                ctx.accounts.yes_mint.to_account_info(),
-                ctx.accounts.user_yes_ata.to_account_info(),
-                ctx.accounts.user_yes_ata.amount,
-            ),
-            Outcome::No => (
-                ctx.accounts.no_mint.to_account_info(),
-                ctx.accounts.user_no_ata.to_account_info(),
-                ctx.accounts.user_no_ata.amount,
-            ),
-            Outcome::Pending | Outcome::Invalid => {
-                return err!(NflError::CannotRedeemForOutcome);
-            }
-        };
-
-        require!(winner_amount > 0, NflError::NothingToRedeem);
+        // Burn YES from user
+        {
+            let burn_accounts = token::Burn {
+                mint: ctx.accounts.yes_mint.to_account_info(),
+                from: ctx.accounts.user_yes_ata.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+            token::burn(cpi_ctx, amount)?;
+        }
 
-        // Burn the winning tokens
+        // Burn NO from user
         {
             let burn_accounts = token::Burn {
-                mint: winner_mint_ai.clone(),
-                from: winner_ata_ai.clone(),
+                mint: ctx.accounts.no_mint.to_account_info(),
+                from: ctx.accounts.user_no_ata.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             };
             let cpi_ctx =
                 CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
-            token::burn(cpi_ctx, winner_amount)?;
+            token::burn(cpi_ctx, amount)?;
         }
 
         // Transfer collateral from vault to user, signed by PDA
@@ -260,291 +436,4055 @@ pub mod nfl_blockchain {
             transfer_accounts,
             signer_seeds,
         );
-        token::transfer(cpi_ctx, winner_amount)?;
+        token::transfer(cpi_ctx, amount)?;
 
-        msg!(
-            "Redeemed {} units of {:?} for user {} in market {}",
-            winner_amount,
-            market.outcome,
-            ctx.accounts.user.key(),
-            market.key()
-        );
+        emit_cpi!(PairsMerged {
+            market: market.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+        });
 
         Ok(())
     }
 
-    // -------------------------------------------------------------------------
-    // NEW: ORDER BOOK FUNCTIONALITY
-    // -------------------------------------------------------------------------
+    /// Halt an open market.
+    /// While halted, minting and every trading path are rejected;
+    /// cancels, merges and resolution remain available.
+    pub fn halt_market(ctx: Context<UpdateMarketStatus>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Open, NflError::MarketNotOpen);
 
-    /// Initialize a new OrderBook account.
-    pub fn initialize_order_book(ctx: Context<InitializeOrderBook>) -> Result<()> {
-        let ob = &mut ctx.accounts.order_book;
-        // Link this order book to the specific market it serves
-        ob.market = ctx.accounts.market.key();
-        ob.next_order_id = 0;
-        ob.capacity = 100;
-        msg!("Order Book initialized for Market: {}", ctx.accounts.market.key());
+        market.status = MarketStatus::Halted;
+
+        let changed = MarketStatusChanged { market: market.key(), status: market.status };
+        emit_cpi!(changed);
         Ok(())
     }
 
-    /// Place a Limit Sell Order.
-    /// This escrows the Seller's outcome tokens (YES or NO) into the vault 
-    /// and records their desire to sell at a specific price.
-    /// 
-    /// Price is stored as a scaled integer: price = actual_price * PRICE_SCALE
-    /// Example: price = 1_500_000_000 represents 1.5, price = 500_000_000 represents 0.5
-    /// When calculating payment, the cost is rounded down: cost = (price * quantity) / PRICE_SCALE
-    pub fn place_limit_sell(ctx: Context<PlaceLimitSell>, price: u64, quantity: u64, is_yes: bool) -> Result<()> {
-        require!(quantity > 0, NflError::InvalidAmount);
-        
-        // Determine which tokens to escrow (YES tokens or NO tokens)
-        // and which vault they should go to.
-        let (from_account, to_vault) = if is_yes {
-            (&ctx.accounts.seller_token_ata, &ctx.accounts.yes_vault)
-        } else {
-            (&ctx.accounts.seller_token_ata, &ctx.accounts.no_vault)
-        };
+    /// Resume trading on a halted market.
+    pub fn resume_market(ctx: Context<UpdateMarketStatus>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Halted, NflError::MarketNotHalted);
 
-        // 1. Escrow Transfer: Move tokens from Seller -> OrderBook Vault
-        // This ensures the tokens are available immediately when a buyer arrives.
-        let cpi_accounts = Transfer {
-            from: from_account.to_account_info(),
-            to: to_vault.to_account_info(),
-            authority: ctx.accounts.seller.to_account_info(),
-        };
-        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), quantity)?;
+        market.status = MarketStatus::Open;
 
-        // 2. Update State: Add the order to the on-chain vector
-        let ob = &mut ctx.accounts.order_book;
-        let order_id = ob.next_order_id;
-        ob.next_order_id = order_id.checked_add(1).unwrap();
+        let changed = MarketStatusChanged { market: market.key(), status: market.status };
+        emit_cpi!(changed);
+        Ok(())
+    }
 
-        // Check capacity to prevent exceeding account size limits
-        if ob.orders.len() as u64 >= ob.capacity {
-            return err!(NflError::OrderBookFull);
-        }
+    /// Hand the resolver role to another key, e.g. to rotate an oracle's hot key.
+    /// Only the market authority may do this, and only before expiry.
+    pub fn set_resolver(ctx: Context<SetResolver>, resolver: Pubkey) -> Result<()> {
+        let market = &mut ctx.accounts.market;
 
-        // Push the order struct. Note: We store the seller's collateral ATA 
-        // so we know where to send the USDC when this order is filled.
-        ob.orders.push(Order {
-            id: order_id,
-            owner: ctx.accounts.seller.key(),
-            seller_receive_collateral_ata: ctx.accounts.seller_receive_collateral_ata.key(),
-            price,
-            quantity,
-            is_yes,
-        });
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < market.expiry_ts, NflError::ResolverLocked);
 
-        msg!("Order Placed: ID={}, Price={}, Qty={}, IsYes={}", order_id, price, quantity, is_yes);
+        market.resolver = resolver;
+
+        msg!("Market {} resolver set to {}", market.key(), resolver);
         Ok(())
     }
 
-    /// Market Buy: Fills orders starting from the oldest/best price until quantity is met.
-    /// Uses 'remaining_accounts' to pay arbitrary sellers.
-    pub fn market_buy<'info>(
-        ctx: Context<'_, '_, '_, 'info, MarketBuyAccounts<'info>>, 
-        params: MarketBuyParams
-    ) -> Result<()> {
-        let mut quantity_to_buy = params.quantity;
-        let want_yes = params.want_yes;
+    /// Designate the wallet whose collateral account receives leftovers when the market is closed.
+    pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.treasury = treasury;
 
-        // RUST BORROW CHECKER WORKAROUND:
-        // We need 'order_book_info' (immutable) for the CPI signer later.
-        // But we also need 'ob' (mutable) to modify the orders vector.
-        // We must extract the immutable reference *before* taking the mutable borrow.
-        let order_book_info = ctx.accounts.order_book.to_account_info();
-        let ob = &mut ctx.accounts.order_book;
-        
-        // Iterator for sellers passed in via 'remaining_accounts'
-        let mut remaining_iter = ctx.remaining_accounts.iter();
-
-        // Sort primarily by Price (Ascending), then by ID (Ascending)
-        // This ensures that buying at best possible price 
-        // Should probably implement more efficient solution later
-        ob.orders.sort_by(|a, b| {
-            let price_cmp = a.price.cmp(&b.price);
-            if price_cmp == std::cmp::Ordering::Equal {
-                a.id.cmp(&b.id)
-            } else {
-                price_cmp
-            }
-        });
+        msg!("Market {} treasury set to {}", market.key(), treasury);
+        Ok(())
+    }
 
-        // Prepare PDA signer seeds (needed to unlock tokens from the Vault)
-        let market_key = ctx.accounts.market.key();
-        let bump = ctx.bumps.order_book;
-        let seeds = &[
-            b"orderbook",
-            market_key.as_ref(),
-            &[bump],
-        ];
-        let signer = &[&seeds[..]];
+    /// Resolve a market without a challenge period in one step, as a proposal finalized at once.
+    /// Only the market's resolver may call this. Markets with a challenge period must go through
+    /// `propose_resolution` and `finalize_resolution` instead, and committee markets through
+    /// `vote_resolution`. No bond is posted, since nobody can dispute the outcome.
+    pub fn resolve_market(ctx: Context<ResolveMarket>, outcome: Outcome) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            market.status != MarketStatus::Resolved,
+            NflError::MarketAlreadyResolved
+        );
+
+        require!(market.committee.is_empty(), NflError::CommitteeMarket);
+        require!(market.challenge_period_secs == 0, NflError::ChallengePeriodRequired);
 
-        let mut i = 0;
-        // Loop through orders until we satisfy the buy quantity or run out of orders
-        while i < ob.orders.len() && quantity_to_buy > 0 {
-            let order = &mut ob.orders[i];
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= market.expiry_ts, NflError::MarketNotExpired);
 
-            // Skip orders that don't match the side we want (YES vs NO)
-            if order.is_yes != want_yes { i += 1; continue; }
-            
-            // Cleanup: remove empty orders if encountered
-            if order.quantity == 0 { ob.orders.remove(i); continue; }
+        let resolver = ctx.accounts.resolver.key();
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.market = market.key();
+        proposal.proposer = resolver;
+        proposal.outcome = outcome;
+        proposal.bond = 0;
+        proposal.challenge_ends_ts = now;
+        proposal.disputer = None;
+        proposal.bond_winner = Some(resolver);
 
-            // Determine how much to fill from this specific order
-            let fill_amount = order.quantity.min(quantity_to_buy);
+        let resolved = settle_market(market, outcome)?;
+        emit_cpi!(resolved);
 
-            // Fetch the specific Seller's account from remaining_accounts
-            let seller_collateral_ata_info = remaining_iter.next().ok_or(NflError::MissingSellerAccounts)?;
+        Ok(())
+    }
 
-            // SECURITY CHECK: Ensure the account passed matches the order's owner
-            if seller_collateral_ata_info.key() != order.seller_receive_collateral_ata {
-                return err!(NflError::SellerAccountMismatch);
-            }
+    /// Propose a final outcome, posting `resolution_bond` collateral into the market's bond vault.
+    /// Only the market's resolver may propose. Trading stops, and the proposal becomes final
+    /// through `finalize_resolution` unless someone disputes it within the challenge period.
+    /// Binary markets take Yes / No / Tie / Invalid; scalar markets take the final
+    /// numeric value as `Outcome::Scalar(value)`, or Invalid to refund both sides.
+    pub fn propose_resolution(ctx: Context<ProposeResolution>, outcome: Outcome) -> Result<()> {
+        let market = &mut ctx.accounts.market;
 
-            // Calculate cost with fractional price support: cost = (price * fill_amount) / PRICE_SCALE
-            // Round down by using integer division
-            let cost = (order.price as u128)
-                .checked_mul(fill_amount as u128)
-                .ok_or(NflError::MathOverflow)?
-                .checked_div(PRICE_SCALE as u128)
-                .ok_or(NflError::MathOverflow)? as u64;
+        require!(
+            market.status != MarketStatus::Resolved,
+            NflError::MarketAlreadyResolved
+        );
 
-            // 1. Payment Transfer: Buyer pays Seller (Collateral/USDC) directly
-            let cpi_pay = Transfer {
-                from: ctx.accounts.buyer_collateral_ata.to_account_info(),
-                to: seller_collateral_ata_info.clone(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            };
-            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_pay), cost)?;
+        require!(market.committee.is_empty(), NflError::CommitteeMarket);
 
-            // 2. Asset Transfer: Vault releases Outcome Tokens to Buyer
-            // Signed by the OrderBook PDA
-            let vault = if want_yes { ctx.accounts.yes_vault.to_account_info() } else { ctx.accounts.no_vault.to_account_info() };
-            let cpi_receive = Transfer {
-                from: vault,
-                to: ctx.accounts.buyer_receive_token_ata.to_account_info(),
-                authority: order_book_info.clone(), // Use the immutable reference here
-            };
-            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_receive, signer), fill_amount)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= market.expiry_ts, NflError::MarketNotExpired);
+        require!(market.payout_split(outcome).is_some(), NflError::InvalidResolutionOutcome);
 
-            // Update state
-            order.quantity -= fill_amount;
-            quantity_to_buy -= fill_amount;
+        let bond = market.resolution_bond;
+        let challenge_ends_ts = now
+            .checked_add(market.challenge_period_secs)
+            .ok_or(NflError::MathOverflow)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.market = market.key();
+        proposal.proposer = ctx.accounts.resolver.key();
+        proposal.outcome = outcome;
+        proposal.bond = bond;
+        proposal.challenge_ends_ts = challenge_ends_ts;
+        proposal.disputer = None;
+        proposal.bond_winner = None;
+
+        market.status = MarketStatus::Proposed;
+
+        let transfer_accounts = token::Transfer {
+            from: ctx.accounts.resolver_collateral_ata.to_account_info(),
+            to: ctx.accounts.bond_vault.to_account_info(),
+            authority: ctx.accounts.resolver.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts);
+        token::transfer(cpi_ctx, bond)?;
+
+        emit_cpi!(ResolutionProposed {
+            market: market.key(),
+            proposer: ctx.accounts.resolver.key(),
+            outcome,
+            bond,
+            challenge_ends_ts,
+        });
 
-            // If order is fully filled, remove it. Else, move to next.
-            if order.quantity == 0 { ob.orders.remove(i); } else { i += 1; }
-        }
-        
         Ok(())
     }
 
-    /// Buy Exact: Identical to Market Buy, but verifies liquidity and price constraints FIRST.
-    /// This is atomic: if the full quantity cannot be bought under max_price, the transaction fails.
-    pub fn buy_exact<'info>(
-        ctx: Context<'_, '_, '_, 'info, MarketBuyAccounts<'info>>, 
-        params: BuyExactParams
-    ) -> Result<()> {
-        let mut quantity_to_buy = params.quantity;
-        let want_yes = params.want_yes;
+    /// Dispute a pending proposal by posting a matching bond.
+    /// Anyone may dispute before the challenge period ends; the market then waits
+    /// for the escalation resolver's ruling in `resolve_dispute`.
+    pub fn dispute(ctx: Context<Dispute>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Proposed, NflError::NoPendingProposal);
 
-        let order_book_info = ctx.accounts.order_book.to_account_info();
-        let ob = &mut ctx.accounts.order_book;
-        let mut remaining_iter = ctx.remaining_accounts.iter();
-
-        // Sort primarily by Price (Ascending), then by ID (Ascending)
-        // This ensures that buying at best possible price
-        ob.orders.sort_by(|a, b| {
-            let price_cmp = a.price.cmp(&b.price);
-            if price_cmp == std::cmp::Ordering::Equal {
-                a.id.cmp(&b.id)
-            } else {
-                price_cmp
-            }
-        });
+        let proposal = &mut ctx.accounts.proposal;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < proposal.challenge_ends_ts, NflError::ChallengePeriodOver);
 
-        // Check if the trade is possible BEFORE moving any funds.
-        let mut needed = quantity_to_buy;
-        for order in ob.orders.iter() {
-            if order.is_yes != want_yes { continue; }
-            if needed == 0 { break; }
-            
-            // If we hit an order that is too expensive, the whole trade fails
-            if order.price > params.max_price { return err!(NflError::TooExpensive); }
-            
-            needed = needed.saturating_sub(order.quantity);
-        }
-        // If we processed all orders and still need tokens, fail.
-        if needed > 0 { return err!(NflError::InsufficientLiquidity); }
-
-        // Execute Trade (see more details in market_buy above)
-        let market_key = ctx.accounts.market.key();
-        let bump = ctx.bumps.order_book;
-        let seeds = &[
-            b"orderbook",
-            market_key.as_ref(),
-            &[bump],
-        ];
-        let signer = &[&seeds[..]];
+        proposal.disputer = Some(ctx.accounts.disputer.key());
+        market.status = MarketStatus::Disputed;
 
-        let mut i = 0;
-        while i < ob.orders.len() && quantity_to_buy > 0 {
-            let order = &mut ob.orders[i];
-            if order.is_yes != want_yes { i += 1; continue; }
-            if order.quantity == 0 { ob.orders.remove(i); continue; }
+        let transfer_accounts = token::Transfer {
+            from: ctx.accounts.disputer_collateral_ata.to_account_info(),
+            to: ctx.accounts.bond_vault.to_account_info(),
+            authority: ctx.accounts.disputer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts);
+        token::transfer(cpi_ctx, proposal.bond)?;
 
-            let fill_amount = order.quantity.min(quantity_to_buy);
-            let seller_collateral_ata_info = remaining_iter.next().ok_or(NflError::MissingSellerAccounts)?;
+        let disputed = ResolutionDisputed {
+            market: market.key(),
+            disputer: ctx.accounts.disputer.key(),
+            proposed_outcome: proposal.outcome,
+            bond: proposal.bond,
+        };
+        emit_cpi!(disputed);
 
-            if seller_collateral_ata_info.key() != order.seller_receive_collateral_ata {
-                return err!(NflError::SellerAccountMismatch);
-            }
+        Ok(())
+    }
 
-            // Calculate cost with fractional price support: cost = (price * fill_amount) / PRICE_SCALE
-            // Round down by using integer division
-            let cost = (order.price as u128)
-                .checked_mul(fill_amount as u128)
-                .unwrap()
-                .checked_div(PRICE_SCALE as u128)
-                .unwrap() as u64;
+    /// Settle an undisputed proposal once its challenge period has passed.
+    /// Permissionless; the proposer can then take its bond back with `claim_bond`.
+    pub fn finalize_resolution(ctx: Context<FinalizeResolution>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Proposed, NflError::NoPendingProposal);
 
-            // Buyer pays Seller
-            let cpi_pay = Transfer {
-                from: ctx.accounts.buyer_collateral_ata.to_account_info(),
-                to: seller_collateral_ata_info.clone(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            };
-            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_pay), cost)?;
+        let proposal = &mut ctx.accounts.proposal;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= proposal.challenge_ends_ts, NflError::ChallengePeriodActive);
 
-            // Vault releases tokens to Buyer
-            let vault = if want_yes { ctx.accounts.yes_vault.to_account_info() } else { ctx.accounts.no_vault.to_account_info() };
-            let cpi_receive = Transfer {
-                from: vault,
-                to: ctx.accounts.buyer_receive_token_ata.to_account_info(),
-                authority: order_book_info.clone(),
-            };
-            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_receive, signer), fill_amount)?;
+        let resolved = settle_market(market, proposal.outcome)?;
+        emit_cpi!(resolved);
 
-            order.quantity -= fill_amount;
-            quantity_to_buy -= fill_amount;
+        proposal.bond_winner = Some(proposal.proposer);
 
-            if order.quantity == 0 { ob.orders.remove(i); } else { i += 1; }
-        }
         Ok(())
     }
-}
-// --- Accounts ---
 
-#[derive(Accounts)]
-pub struct CreateMarket<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Rule on a disputed proposal. Only the market's escalation resolver may call this.
+    /// Both bonds go to the proposer if the ruling upholds the proposal, else to the disputer,
+    /// who collects them with `claim_bond`.
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, outcome: Outcome) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Disputed, NflError::NotDisputed);
+
+        let resolved = settle_market(market, outcome)?;
+        emit_cpi!(resolved);
+
+        let proposal = &mut ctx.accounts.proposal;
+        let winner = if outcome == proposal.outcome {
+            proposal.proposer
+        } else {
+            proposal.disputer.ok_or(NflError::NotDisputed)?
+        };
+        proposal.bond_winner = Some(winner);
+
+        msg!(
+            "Market {} dispute ruled {:?}; bonds awarded to {}",
+            market.key(),
+            outcome,
+            winner
+        );
+
+        Ok(())
+    }
+
+    /// Pay the bonds held for a settled proposal to their winner, into any collateral
+    /// account they choose. Pulled by the winner rather than pushed at settlement, so a
+    /// closed or frozen token account cannot hold up resolution.
+    pub fn claim_bond(ctx: Context<ClaimBond>) -> Result<()> {
+        let amount = ctx.accounts.bond_vault.amount;
+        require!(amount > 0, NflError::NoBondToClaim);
+
+        pay_bond(
+            &ctx.accounts.market,
+            &ctx.accounts.bond_vault,
+            &ctx.accounts.winner_collateral_ata,
+            &ctx.accounts.market_authority,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        emit_cpi!(BondClaimed {
+            market: ctx.accounts.market.key(),
+            winner: ctx.accounts.winner.key(),
+            recipient: ctx.accounts.winner_collateral_ata.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Record a committee member's vote on the outcome of a committee market.
+    /// Votes stay on the market, so conflicting votes remain visible. A member may change
+    /// their vote until the market resolves, which lets a split committee converge.
+    /// The market resolves as soon as `committee_threshold` members agree on an outcome.
+    pub fn vote_resolution(ctx: Context<VoteResolution>, outcome: Outcome) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            market.status != MarketStatus::Resolved,
+            NflError::MarketAlreadyResolved
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= market.expiry_ts, NflError::MarketNotExpired);
+        require!(market.payout_split(outcome).is_some(), NflError::InvalidResolutionOutcome);
+
+        let member = ctx.accounts.member.key();
+        let index = market
+            .committee
+            .iter()
+            .position(|key| *key == member)
+            .ok_or(NflError::NotCommitteeMember)?;
+        require!(market.committee_votes[index] != Some(outcome), NflError::AlreadyVoted);
+
+        market.committee_votes[index] = Some(outcome);
+
+        let agreeing = market.committee_votes.iter().filter(|vote| **vote == Some(outcome)).count();
+        let voted = CommitteeVoted {
+            market: market.key(),
+            member,
+            outcome,
+            agreeing: agreeing as u8,
+            threshold: market.committee_threshold,
+        };
+        emit_cpi!(voted);
+
+        if agreeing >= market.committee_threshold as usize {
+            let resolved = settle_market(market, outcome)?;
+            emit_cpi!(resolved);
+        }
+
+        Ok(())
+    }
+
+    /// Redeem the full YES/NO balances for collateral according to the market's payout split.
+    /// Every side with a non-zero payout is burned and paid pro rata:
+    /// payout = (yes * payout_yes_bps + no * payout_no_bps) / BPS_DENOMINATOR, rounded down once.
+    /// The payout goes to `recipient_collateral_ata` when supplied, else `user_collateral_ata`.
+    pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
+        redeem_tokens(ctx, None)
+    }
+
+    /// Like `redeem`, but burns at most `amount` tokens from each paying side.
+    pub fn redeem_partial(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+        require!(amount > 0, NflError::InvalidAmount);
+        redeem_tokens(ctx, Some(amount))
+    }
+
+    // -------------------------------------------------------------------------
+    // CATEGORICAL MARKETS
+    // -------------------------------------------------------------------------
+
+    /// Create a categorical market on `num_outcomes` mutually exclusive outcomes,
+    /// e.g. "Which team wins the AFC East?". Each outcome gets its own mint, added
+    /// one per call with `add_outcome_mint` before complete sets can be minted.
+    /// As for binary markets, complete sets are only minted inside `trading_window`,
+    /// and `resolver` rather than the authority settles the market.
+    pub fn create_categorical_market(
+        ctx: Context<CreateCategoricalMarket>,
+        expiry_ts: i64,
+        num_outcomes: u8,
+        trading_window: TradingWindow,
+        resolver: Pubkey,
+    ) -> Result<()> {
+        require!(expiry_ts > 0, NflError::InvalidExpiry);
+        require!(
+            (2..=CategoricalMarket::MAX_OUTCOMES).contains(&num_outcomes),
+            NflError::InvalidOutcomeCount
+        );
+        require!(trading_window.open_ts < trading_window.close_ts, NflError::InvalidTradingWindow);
+        require!(trading_window.close_ts <= expiry_ts, NflError::InvalidTradingWindow);
+
+        let market = &mut ctx.accounts.market;
+
+        market.authority = ctx.accounts.authority.key();
+        market.resolver = resolver;
+        market.base_mint = ctx.accounts.base_mint.key();
+        market.vault = ctx.accounts.vault.key();
+        market.expiry_ts = expiry_ts;
+        market.trading_open_ts = trading_window.open_ts;
+        market.trading_close_ts = trading_window.close_ts;
+        market.status = MarketStatus::Open;
+        market.num_outcomes = num_outcomes;
+        market.outcome_mints = Vec::with_capacity(num_outcomes as usize);
+        market.outcome = CategoricalOutcome::Pending;
+        market.market_authority_bump = ctx.bumps.market_authority;
+
+        let created = CategoricalMarketCreated {
+            market: market.key(),
+            authority: market.authority,
+            resolver,
+            base_mint: market.base_mint,
+            vault: market.vault,
+            num_outcomes,
+            expiry_ts,
+            trading_open_ts: market.trading_open_ts,
+            trading_close_ts: market.trading_close_ts,
+        };
+        emit_cpi!(created);
+
+        Ok(())
+    }
+
+    /// Create the mint for outcome `index`. Outcomes are added in order, so `index`
+    /// must be the next outcome without a mint. The mint is a PDA of the market
+    /// and is minted and burned under the `market_auth` PDA.
+    pub fn add_outcome_mint(ctx: Context<AddOutcomeMint>, index: u8) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(
+            index < market.num_outcomes && index as usize == market.outcome_mints.len(),
+            NflError::InvalidOutcomeIndex
+        );
+
+        market.outcome_mints.push(ctx.accounts.outcome_mint.key());
+
+        msg!(
+            "Outcome {} of market {} uses mint {}",
+            index,
+            market.key(),
+            ctx.accounts.outcome_mint.key()
+        );
+
+        Ok(())
+    }
+
+    /// Mint complete sets: deposit `amount` collateral and receive `amount` of every outcome token.
+    /// `remaining_accounts` holds (outcome mint, user token account) pairs in outcome order.
+    pub fn mint_sets<'info>(ctx: Context<'_, '_, 'info, 'info, CompleteSets<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, NflError::InvalidAmount);
+
+        let market = &ctx.accounts.market;
+        market.require_trading_open()?;
+
+        let outcomes = outcome_set_accounts(market, &ctx.accounts.user.key(), ctx.remaining_accounts)?;
+
+        // Transfer collateral from user to market vault
+        {
+            let cpi_accounts = token::Transfer {
+                from: ctx.accounts.user_collateral_ata.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::transfer(cpi_ctx, amount)?;
+        }
+
+        // PDA seeds for market_authority
+        let market_key = market.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"market_auth",
+            market_key.as_ref(),
+            &[market.market_authority_bump],
+        ];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        // Mint every outcome to user
+        for (mint, user_ata) in outcomes {
+            let cpi_accounts = token::MintTo {
+                mint,
+                to: user_ata,
+                authority: ctx.accounts.market_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token::mint_to(cpi_ctx, amount)?;
+        }
+
+        emit_cpi!(SetsMinted {
+            market: market.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Merge complete sets back into collateral.
+    /// Burns `amount` of every outcome token and releases the same amount from the vault.
+    /// Allowed until the market is resolved; accounts are passed as for `mint_sets`.
+    pub fn merge_sets<'info>(ctx: Context<'_, '_, 'info, 'info, CompleteSets<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, NflError::InvalidAmount);
+
+        let market = &ctx.accounts.market;
+        require!(
+            market.status != MarketStatus::Resolved,
+            NflError::MarketAlreadyResolved
+        );
+
+        let outcomes = outcome_set_accounts(market, &ctx.accounts.user.key(), ctx.remaining_accounts)?;
+
+        // Burn every outcome from user
+        for (mint, user_ata) in outcomes {
+            let burn_accounts = token::Burn {
+                mint,
+                from: user_ata,
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+            token::burn(cpi_ctx, amount)?;
+        }
+
+        // Transfer collateral from vault to user, signed by PDA
+        let market_key = market.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"market_auth",
+            market_key.as_ref(),
+            &[market.market_authority_bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[signer_seeds];
+
+        let transfer_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_collateral_ata.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        emit_cpi!(SetsMerged {
+            market: market.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Halt an open categorical market; merges and resolution remain available.
+    pub fn halt_categorical_market(ctx: Context<UpdateCategoricalMarketStatus>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Open, NflError::MarketNotOpen);
+
+        market.status = MarketStatus::Halted;
+
+        let changed = MarketStatusChanged { market: market.key(), status: market.status };
+        emit_cpi!(changed);
+        Ok(())
+    }
+
+    /// Resume minting on a halted categorical market.
+    pub fn resume_categorical_market(ctx: Context<UpdateCategoricalMarketStatus>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Halted, NflError::MarketNotHalted);
+
+        market.status = MarketStatus::Open;
+
+        let changed = MarketStatusChanged { market: market.key(), status: market.status };
+        emit_cpi!(changed);
+        Ok(())
+    }
+
+    /// Hand the resolver role of a categorical market to another key.
+    /// Only the market authority may do this, and only before expiry.
+    pub fn set_categorical_resolver(ctx: Context<SetCategoricalResolver>, resolver: Pubkey) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < market.expiry_ts, NflError::ResolverLocked);
+
+        market.resolver = resolver;
+
+        msg!("Categorical market {} resolver set to {}", market.key(), resolver);
+        Ok(())
+    }
+
+    /// Resolve a categorical market to the outcome at `Winner(index)`, or void it with `Invalid`.
+    /// Only the market's resolver may call this, once the market has expired.
+    pub fn resolve_categorical_market(ctx: Context<ResolveCategoricalMarket>, outcome: CategoricalOutcome) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            market.status != MarketStatus::Resolved,
+            NflError::MarketAlreadyResolved
+        );
+
+        // A market missing outcome mints never minted a set, but its outcomes must still all exist
+        // before one of them can win
+        require!(
+            market.outcome_mints.len() == market.num_outcomes as usize,
+            NflError::OutcomeMintsIncomplete
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= market.expiry_ts, NflError::MarketNotExpired);
+
+        match outcome {
+            CategoricalOutcome::Pending => return err!(NflError::InvalidResolutionOutcome),
+            CategoricalOutcome::Winner(index) => require!(
+                (index as usize) < market.outcome_mints.len(),
+                NflError::InvalidOutcomeIndex
+            ),
+            CategoricalOutcome::Invalid => {}
+        }
+
+        market.status = MarketStatus::Resolved;
+        market.outcome = outcome;
+
+        let resolved = CategoricalMarketResolved { market: market.key(), outcome, resolved_ts: now };
+        emit_cpi!(resolved);
+
+        Ok(())
+    }
+
+    /// Redeem winning outcome tokens of a categorical market 1:1 for collateral.
+    /// `amount` caps the tokens burned (None redeems the full balance).
+    /// The payout goes to `recipient_collateral_ata` when supplied, else `user_collateral_ata`.
+    pub fn redeem_categorical(ctx: Context<RedeemCategorical>, amount: Option<u64>) -> Result<()> {
+        let market = &ctx.accounts.market;
+
+        require!(
+            market.status == MarketStatus::Resolved,
+            NflError::MarketNotResolved
+        );
+        let winning_mint = match market.outcome {
+            CategoricalOutcome::Winner(index) => market.outcome_mints[index as usize],
+            // A voided market is refunded through `refund_categorical`
+            _ => return err!(NflError::CannotRedeemForOutcome),
+        };
+        require_keys_eq!(
+            ctx.accounts.outcome_mint.key(),
+            winning_mint,
+            NflError::CannotRedeemForOutcome
+        );
+
+        if let Some(amount) = amount {
+            require!(amount > 0, NflError::InvalidAmount);
+        }
+        let balance = ctx.accounts.user_outcome_ata.amount;
+        let payout = amount.map_or(balance, |a| a.min(balance));
+
+        require!(payout > 0, NflError::NothingToRedeem);
+
+        // Burn the redeemed tokens
+        {
+            let burn_accounts = token::Burn {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                from: ctx.accounts.user_outcome_ata.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+            token::burn(cpi_ctx, payout)?;
+        }
+
+        let recipient = match &ctx.accounts.recipient_collateral_ata {
+            Some(recipient) => recipient.to_account_info(),
+            None => ctx.accounts.user_collateral_ata.to_account_info(),
+        };
+
+        // Transfer collateral from vault to the recipient, signed by PDA
+        let market_key = market.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"market_auth",
+            market_key.as_ref(),
+            &[market.market_authority_bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[signer_seeds];
+
+        let transfer_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: recipient.clone(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, payout)?;
+
+        emit_cpi!(CategoricalRedeemed {
+            market: market.key(),
+            user: ctx.accounts.user.key(),
+            recipient: recipient.key(),
+            outcome: market.outcome,
+            burned: payout,
+            payout,
+        });
+
+        Ok(())
+    }
+
+    /// Refund a voided (`Invalid`) categorical market. Every outcome token is worth 1/N of a unit,
+    /// so the user's whole balance of each outcome is burned and
+    /// payout = (sum of balances) / num_outcomes, rounded down once.
+    /// Accounts are passed as for `mint_sets`, one pair per outcome.
+    pub fn refund_categorical<'info>(ctx: Context<'_, '_, 'info, 'info, CompleteSets<'info>>) -> Result<()> {
+        let market = &ctx.accounts.market;
+
+        require!(
+            market.status == MarketStatus::Resolved,
+            NflError::MarketNotResolved
+        );
+        require!(
+            market.outcome == CategoricalOutcome::Invalid,
+            NflError::CannotRedeemForOutcome
+        );
+
+        let outcomes = outcome_set_accounts(market, &ctx.accounts.user.key(), ctx.remaining_accounts)?;
+
+        let mut balances = Vec::with_capacity(outcomes.len());
+        let mut total: u64 = 0;
+        for (_, user_ata) in &outcomes {
+            // Already checked as the user's token account of this outcome
+            let balance = TokenAccount::try_deserialize(&mut &user_ata.try_borrow_data()?[..])?.amount;
+            total = total.checked_add(balance).ok_or(NflError::MathOverflow)?;
+            balances.push(balance);
+        }
+        let payout = total / market.num_outcomes as u64;
+
+        require!(payout > 0, NflError::NothingToRedeem);
+
+        // Burn every outcome balance
+        for ((mint, user_ata), balance) in outcomes.into_iter().zip(balances) {
+            if balance == 0 {
+                continue;
+            }
+            let burn_accounts = token::Burn {
+                mint,
+                from: user_ata,
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+            token::burn(cpi_ctx, balance)?;
+        }
+
+        // Transfer collateral from vault to user, signed by PDA
+        let market_key = market.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"market_auth",
+            market_key.as_ref(),
+            &[market.market_authority_bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[signer_seeds];
+
+        let transfer_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_collateral_ata.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, payout)?;
+
+        emit_cpi!(CategoricalRedeemed {
+            market: market.key(),
+            user: ctx.accounts.user.key(),
+            recipient: ctx.accounts.user_collateral_ata.key(),
+            outcome: market.outcome,
+            burned: total,
+            payout,
+        });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    // NEW: ORDER BOOK FUNCTIONALITY
+    // -------------------------------------------------------------------------
+
+    /// Initialize a new OrderBook account with room for `capacity` resting orders.
+    /// Only the market authority can create the market's book.
+    /// The account is created in a single instruction, so it is limited to 10 KiB
+    /// (about 100 orders); use `grow_order_book` to add more slots afterwards.
+    ///
+    /// Every order must be priced on a multiple of `tick_size` and sized in multiples of `lot_size`,
+    /// with at least `min_order_size` tokens, so dust orders cannot crowd out the book's slots.
+    /// `tick_size * lot_size` must be a multiple of PRICE_SCALE, so every order costs a whole
+    /// number of collateral units and no fill is rounded.
+    pub fn initialize_order_book(
+        ctx: Context<InitializeOrderBook>,
+        capacity: u64,
+        tick_size: u64,
+        lot_size: u64,
+        min_order_size: u64,
+    ) -> Result<()> {
+        require!(capacity > 0, NflError::InvalidOrderBookCapacity);
+
+        let mut ob = ctx.accounts.order_book.load_init()?;
+        // Link this order book to the specific market it serves
+        ob.market = ctx.accounts.market.key();
+        ob.next_order_id = 0;
+        ob.capacity = capacity;
+        ob.order_count = 0;
+        ob.maker_fee_bps = 0;
+        ob.taker_fee_bps = 0;
+        ob.set_grid(tick_size, lot_size, min_order_size)?;
+        msg!(
+            "Order Book initialized for Market: {}, Capacity={}, TickSize={}, LotSize={}, MinOrderSize={}",
+            ctx.accounts.market.key(),
+            capacity,
+            tick_size,
+            lot_size,
+            min_order_size
+        );
+        Ok(())
+    }
+
+    /// Grow an order book by `additional_capacity` order slots.
+    /// The payer funds the extra rent. Each call can add at most 10 KiB of account data,
+    /// so large expansions are done over several instructions.
+    pub fn grow_order_book(ctx: Context<GrowOrderBook>, additional_capacity: u64) -> Result<()> {
+        require!(additional_capacity > 0, NflError::InvalidOrderBookCapacity);
+
+        let order_book_info = ctx.accounts.order_book.to_account_info();
+        let old_capacity = ctx.accounts.order_book.load()?.capacity;
+        let new_capacity = old_capacity
+            .checked_add(additional_capacity)
+            .ok_or(NflError::MathOverflow)?;
+        let new_len = OrderBook::space(new_capacity as usize);
+        require!(
+            new_len.saturating_sub(order_book_info.data_len()) <= MAX_PERMITTED_DATA_INCREASE,
+            ErrorCode::AccountReallocExceedsLimit
+        );
+
+        // Top up rent for the larger account before resizing it
+        let rent_due = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(order_book_info.lamports());
+        if rent_due > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: order_book_info.clone(),
+            };
+            system_program::transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), rent_due)?;
+        }
+
+        order_book_info.resize(new_len)?;
+        ctx.accounts.order_book.load_mut()?.capacity = new_capacity;
+
+        msg!("Order Book grown: Market={}, Capacity={} -> {}", ctx.accounts.market.key(), old_capacity, new_capacity);
+        Ok(())
+    }
+
+    /// Change the book's price and size grid, with the same rules as `initialize_order_book`.
+    /// Orders already resting keep their price and quantity; new orders must follow the new grid.
+    pub fn set_order_book_grid(
+        ctx: Context<SetOrderBookGrid>,
+        tick_size: u64,
+        lot_size: u64,
+        min_order_size: u64,
+    ) -> Result<()> {
+        ctx.accounts.order_book.load_mut()?.set_grid(tick_size, lot_size, min_order_size)?;
+
+        msg!(
+            "Order Book grid set: Market={}, TickSize={}, LotSize={}, MinOrderSize={}",
+            ctx.accounts.market.key(),
+            tick_size,
+            lot_size,
+            min_order_size
+        );
+        Ok(())
+    }
+
+    /// Set the book's trading fees, in basis points of the fill cost.
+    /// Takers pay `taker_fee_bps`. Makers pay `maker_fee_bps`, or receive a rebate when it is
+    /// negative; a rebate may not exceed the taker fee, so every fill nets a non-negative fee.
+    /// Bids already resting keep the maker fee escrowed when they were placed.
+    pub fn set_fees(ctx: Context<SetFees>, maker_fee_bps: i16, taker_fee_bps: u16) -> Result<()> {
+        require!(taker_fee_bps <= BPS_DENOMINATOR, NflError::InvalidFeeConfig);
+        require!(maker_fee_bps.unsigned_abs() <= BPS_DENOMINATOR, NflError::InvalidFeeConfig);
+        require!(maker_fee_bps >= 0 || maker_fee_bps.unsigned_abs() <= taker_fee_bps, NflError::InvalidFeeConfig);
+
+        let mut ob = ctx.accounts.order_book.load_mut()?;
+        ob.maker_fee_bps = maker_fee_bps;
+        ob.taker_fee_bps = taker_fee_bps;
+
+        drop(ob);
+
+        emit_cpi!(FeesSet {
+            market: ctx.accounts.market.key(),
+            maker_fee_bps,
+            taker_fee_bps,
+        });
+        Ok(())
+    }
+
+    /// Pay out `amount` of the collected trading fees to `recipient_collateral_ata`.
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        require!(amount > 0, NflError::InvalidAmount);
+
+        let market_key = ctx.accounts.market.key();
+        let bump = ctx.bumps.order_book;
+        let seeds = &[
+            b"orderbook",
+            market_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.fee_vault.to_account_info(),
+            to: ctx.accounts.recipient_collateral_ata.to_account_info(),
+            authority: ctx.accounts.order_book.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), amount)?;
+
+        emit_cpi!(FeesWithdrawn {
+            market: market_key,
+            recipient: ctx.accounts.recipient_collateral_ata.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Place a Limit Sell Order.
+    /// This escrows the Seller's outcome tokens (YES or NO) into the vault
+    /// and records their desire to sell at a specific price.
+    ///
+    /// Price is stored as a scaled integer: price = actual_price * PRICE_SCALE
+    /// Example: price = 1_000_000_000 represents 1, price = 500_000_000 represents 0.5
+    /// A token never pays out more than 1, so the price must be in (0, PRICE_SCALE].
+    /// When calculating payment, the cost is rounded up: cost = ceil(price * quantity / PRICE_SCALE)
+    ///
+    /// An ask priced at or below the best bid first sells into the bids at `price` or better like
+    /// `market_sell`, with bidders' token accounts in 'remaining_accounts'. GTC and GTT orders then rest
+    /// the remainder; IOC and FOK orders never rest. A `post_only` order never takes liquidity.
+    /// A remainder smaller than the book's `min_order_size` is dropped like an IOC order's.
+    pub fn place_limit_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceLimitSell<'info>>,
+        price: u64,
+        quantity: u64,
+        is_yes: bool,
+        time_in_force: TimeInForce,
+        post_only: Option<PostOnly>,
+        self_trade: SelfTradePrevention,
+    ) -> Result<()> {
+        require!(quantity > 0, NflError::InvalidAmount);
+        ctx.accounts.market.require_trading_open()?;
+        ctx.accounts.order_book.load()?.require_valid_order(Some(price), quantity)?;
+
+        let market = &ctx.accounts.market;
+        let outcome_mint = if is_yes { market.yes_mint } else { market.no_mint };
+        require_keys_eq!(
+            ctx.accounts.seller_token_ata.mint,
+            outcome_mint,
+            NflError::InvalidOutcomeTokenAccount
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let (expiry_ts, rests) = match time_in_force {
+            TimeInForce::Gtc => (0, true),
+            TimeInForce::Gtt { expiry_ts } => {
+                require!(expiry_ts > now, NflError::InvalidOrderExpiry);
+                (expiry_ts, true)
+            }
+            TimeInForce::Ioc | TimeInForce::Fok => (0, false),
+        };
+        require!(rests || post_only.is_none(), NflError::PostOnlyMustRest);
+
+        let mut price = price;
+        let mut quantity = quantity;
+        if let Some(post_only) = post_only {
+            // Post-only asks never take liquidity: reject or reprice one that would cross the best live bid
+            let best_bid = OrderBook::best_order(&ctx.accounts.order_book, is_yes, Side::Bid, now)?;
+            if let Some(best_bid) = best_bid.filter(|best_bid| best_bid.price >= price) {
+                require!(post_only == PostOnly::Slide, NflError::OrderWouldCross);
+                let tick_size = ctx.accounts.order_book.load()?.tick_size;
+                price = best_bid.price.checked_add(tick_size).ok_or(NflError::MathOverflow)?;
+                require!(price <= PRICE_SCALE, NflError::OrderWouldCross);
+            }
+        } else {
+            let matched = {
+                let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+                let seller = ctx.accounts.seller.key();
+                book.match_orders(Side::Bid, is_yes, quantity, Some(price), time_in_force == TimeInForce::Fok, seller, self_trade, now)?
+            };
+
+            let market_key = ctx.accounts.market.key();
+            let bump = ctx.bumps.order_book;
+            let seeds = &[
+                b"orderbook",
+                market_key.as_ref(),
+                &[bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            TakerAccounts {
+                taker: ctx.accounts.seller.to_account_info(),
+                taker_pay_ata: ctx.accounts.seller_token_ata.to_account_info(),
+                taker_receive_ata: ctx.accounts.seller_receive_collateral_ata.to_account_info(),
+                escrow_vault: ctx.accounts.collateral_vault.to_account_info(),
+                fee_vault: ctx.accounts.fee_vault.to_account_info(),
+                order_book: ctx.accounts.order_book.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                makers: ctx.remaining_accounts,
+            }
+            .settle_sell(&matched, signer)?;
+
+            for fill in matched.fills.iter() {
+                emit_cpi!(fill.event(market_key, ctx.accounts.seller.key(), is_yes, Side::Bid));
+            }
+            // A remainder below the book's minimum size is dropped rather than rested
+            let min_order_size = ctx.accounts.order_book.load()?.min_order_size;
+            if !rests || matched.taker_cancelled || matched.remaining == 0 || matched.remaining < min_order_size {
+                return Ok(());
+            }
+            quantity = matched.remaining;
+        }
+
+        // Determine which tokens to escrow (YES tokens or NO tokens)
+        // and which vault they should go to.
+        let (from_account, to_vault) = if is_yes {
+            (&ctx.accounts.seller_token_ata, &ctx.accounts.yes_vault)
+        } else {
+            (&ctx.accounts.seller_token_ata, &ctx.accounts.no_vault)
+        };
+
+        // 1. Escrow Transfer: Move tokens from Seller -> OrderBook Vault
+        // This ensures the tokens are available immediately when a buyer arrives.
+        let cpi_accounts = Transfer {
+            from: from_account.to_account_info(),
+            to: to_vault.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), quantity)?;
+
+        // 2. Update State: Insert the order at its price-time position in the book.
+        // Note: We store the seller's collateral ATA so we know where to send the USDC
+        // when this order is filled. Fails with OrderBookFull if no slot is free.
+        // Designated market makers with active benefits rest ahead of others at the same price
+        // and keep their fee tier and rebate share for the life of the order.
+        let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+        let order_id = book.take_order_id()?;
+        book.insert(Order::new(
+            order_id,
+            ctx.accounts.seller.key(),
+            ctx.accounts.seller_receive_collateral_ata.key(),
+            price,
+            quantity,
+            is_yes,
+            Side::Ask,
+            ctx.accounts.market_maker.as_deref(),
+            0,
+            expiry_ts,
+        ))?;
+
+        drop(book);
+
+        emit_cpi!(OrderPlaced {
+            market: ctx.accounts.market.key(),
+            order_id,
+            owner: ctx.accounts.seller.key(),
+            side: Side::Ask,
+            is_yes,
+            price,
+            quantity,
+            escrow: quantity,
+            expiry_ts,
+        });
+        Ok(())
+    }
+
+    /// Place a Limit Buy Order (bid).
+    /// This escrows the Buyer's collateral into the order book's collateral vault
+    /// and records their desire to buy outcome tokens (YES or NO) at a specific price.
+    ///
+    /// The escrow is the full cost of the order, rounded up: cost = ceil(price * quantity / PRICE_SCALE),
+    /// plus the book's maker fee on that cost (a negative maker fee escrows less, paying the rebate up front).
+    /// Purchased tokens are delivered to `buyer_receive_token_ata` when the bid is filled.
+    ///
+    /// A bid priced at or above the best ask first buys from the asks at `price` or better like
+    /// `market_buy`, with sellers' collateral accounts in 'remaining_accounts'. GTC and GTT bids then rest
+    /// the remainder; IOC and FOK bids never rest. A `post_only` bid never takes liquidity.
+    /// A remainder smaller than the book's `min_order_size` is dropped like an IOC bid's.
+    /// A bid priced above PRICE_SCALE minus the best ask on the other outcome fails with PairPriceAboveScale.
+    pub fn place_limit_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceLimitBuy<'info>>,
+        price: u64,
+        quantity: u64,
+        is_yes: bool,
+        time_in_force: TimeInForce,
+        post_only: Option<PostOnly>,
+        self_trade: SelfTradePrevention,
+    ) -> Result<()> {
+        require!(quantity > 0, NflError::InvalidAmount);
+        ctx.accounts.market.require_trading_open()?;
+        ctx.accounts.order_book.load()?.require_valid_order(Some(price), quantity)?;
+
+        let market = &ctx.accounts.market;
+        let outcome_mint = if is_yes { market.yes_mint } else { market.no_mint };
+        require_keys_eq!(
+            ctx.accounts.buyer_receive_token_ata.mint,
+            outcome_mint,
+            NflError::InvalidOutcomeTokenAccount
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let (expiry_ts, rests) = match time_in_force {
+            TimeInForce::Gtc => (0, true),
+            TimeInForce::Gtt { expiry_ts } => {
+                require!(expiry_ts > now, NflError::InvalidOrderExpiry);
+                (expiry_ts, true)
+            }
+            TimeInForce::Ioc | TimeInForce::Fok => (0, false),
+        };
+        require!(rests || post_only.is_none(), NflError::PostOnlyMustRest);
+
+        let cap = OrderBook::pair_price_cap(&ctx.accounts.order_book, is_yes, now)?;
+        require!(price <= cap, NflError::PairPriceAboveScale);
+
+        let mut price = price;
+        let mut quantity = quantity;
+        if let Some(post_only) = post_only {
+            // Post-only bids never take liquidity: reject or reprice one that would cross the best live ask
+            let best_ask = OrderBook::best_order(&ctx.accounts.order_book, is_yes, Side::Ask, now)?;
+            if let Some(best_ask) = best_ask.filter(|best_ask| best_ask.price <= price) {
+                let tick_size = ctx.accounts.order_book.load()?.tick_size;
+                require!(post_only == PostOnly::Slide && best_ask.price > tick_size, NflError::OrderWouldCross);
+                price = best_ask.price - tick_size;
+            }
+        } else {
+            let matched = {
+                let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+                let buyer = ctx.accounts.buyer.key();
+                book.match_orders(Side::Ask, is_yes, quantity, Some(price), time_in_force == TimeInForce::Fok, buyer, self_trade, now)?
+            };
+
+            let market_key = ctx.accounts.market.key();
+            let bump = ctx.bumps.order_book;
+            let seeds = &[
+                b"orderbook",
+                market_key.as_ref(),
+                &[bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            let vault = if is_yes { &ctx.accounts.yes_vault } else { &ctx.accounts.no_vault };
+            TakerAccounts {
+                taker: ctx.accounts.buyer.to_account_info(),
+                taker_pay_ata: ctx.accounts.buyer_collateral_ata.to_account_info(),
+                taker_receive_ata: ctx.accounts.buyer_receive_token_ata.to_account_info(),
+                escrow_vault: vault.to_account_info(),
+                fee_vault: ctx.accounts.fee_vault.to_account_info(),
+                order_book: ctx.accounts.order_book.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                makers: ctx.remaining_accounts,
+            }
+            .settle_buy(&matched, signer)?;
+
+            for fill in matched.fills.iter() {
+                emit_cpi!(fill.event(market_key, ctx.accounts.buyer.key(), is_yes, Side::Ask));
+            }
+            // A remainder below the book's minimum size is dropped rather than rested
+            let min_order_size = ctx.accounts.order_book.load()?.min_order_size;
+            if !rests || matched.taker_cancelled || matched.remaining == 0 || matched.remaining < min_order_size {
+                return Ok(());
+            }
+            quantity = matched.remaining;
+        }
+
+        // The escrow carries the maker fee at the current rate (less a rebate when negative).
+        // It is fixed once placed, so designated market maker fee benefits, which can be revoked
+        // while the bid rests, do not apply to it
+        let market_maker = ctx.accounts.market_maker.as_deref();
+        let cost = order_cost(price, quantity)?;
+        let maker_fee_bps = ctx.accounts.order_book.load()?.maker_fee_bps;
+        let escrow = apply_signed(cost, maker_fee_for(cost, maker_fee_bps)?)?;
+
+        // 1. Escrow Transfer: Move collateral from Buyer -> OrderBook collateral vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.buyer_collateral_ata.to_account_info(),
+            to: ctx.accounts.collateral_vault.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), escrow)?;
+
+        // 2. Update State: Insert the bid at its price-time position in the book.
+        // For bids we store the buyer's outcome token ATA
+        // so we know where to send the tokens when this order is filled.
+        // Designated market makers with active benefits rest ahead of others at the same price.
+        let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+        let order_id = book.take_order_id()?;
+        book.insert(Order::new(
+            order_id,
+            ctx.accounts.buyer.key(),
+            ctx.accounts.buyer_receive_token_ata.key(),
+            price,
+            quantity,
+            is_yes,
+            Side::Bid,
+            market_maker,
+            escrow,
+            expiry_ts,
+        ))?;
+
+        drop(book);
+
+        emit_cpi!(OrderPlaced {
+            market: ctx.accounts.market.key(),
+            order_id,
+            owner: ctx.accounts.buyer.key(),
+            side: Side::Bid,
+            is_yes,
+            price,
+            quantity,
+            escrow,
+            expiry_ts,
+        });
+        Ok(())
+    }
+
+    /// Market Buy: Fills asks starting from the best price (then oldest) until quantity is met.
+    /// Uses 'remaining_accounts' to pay arbitrary sellers, one per filled order.
+    pub fn market_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarketBuyAccounts<'info>>,
+        params: MarketBuyParams
+    ) -> Result<()> {
+        execute_buy(ctx, params.quantity, params.want_yes, None, params.self_trade)
+    }
+
+    /// Buy Exact: Identical to Market Buy, but all-or-nothing.
+    /// This is atomic: if the full quantity cannot be bought under max_price, the transaction fails.
+    pub fn buy_exact<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarketBuyAccounts<'info>>,
+        params: BuyExactParams
+    ) -> Result<()> {
+        execute_buy(ctx, params.quantity, params.want_yes, Some(params.max_price), params.self_trade)
+    }
+
+    /// Market Sell: Fills bids starting from the best price (then oldest) until quantity is met.
+    /// Uses 'remaining_accounts' to deliver outcome tokens to arbitrary bidders, one per filled order.
+    pub fn market_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarketSellAccounts<'info>>,
+        params: MarketSellParams
+    ) -> Result<()> {
+        execute_sell(ctx, params.quantity, params.sell_yes, None, params.self_trade)
+    }
+
+    /// Sell Exact: Identical to Market Sell, but all-or-nothing.
+    /// This is atomic: if the full quantity cannot be sold at or above min_price, the transaction fails.
+    pub fn sell_exact<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarketSellAccounts<'info>>,
+        params: SellExactParams
+    ) -> Result<()> {
+        execute_sell(ctx, params.quantity, params.sell_yes, Some(params.min_price), params.self_trade)
+    }
+
+    /// Cancel a single resting order.
+    /// Removes the order from the book and returns the escrow to the owner:
+    /// outcome tokens for an ask, collateral for a bid.
+    pub fn cancel_order(ctx: Context<CancelOrder>, order_id: u64) -> Result<()> {
+        let order = {
+            let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+            let index = book.orders().iter().position(|o| o.id == order_id).ok_or(NflError::OrderNotFound)?;
+
+            // Only the wallet that placed the order may pull it
+            require_keys_eq!(book.orders()[index].owner, ctx.accounts.owner.key(), NflError::NotOrderOwner);
+
+            book.remove(index)
+        };
+
+        let (vault, refund) = match order.side() {
+            Side::Ask if order.is_yes() => (&ctx.accounts.yes_vault, order.quantity),
+            Side::Ask => (&ctx.accounts.no_vault, order.quantity),
+            Side::Bid => (&ctx.accounts.collateral_vault, order.escrow),
+        };
+        require_keys_eq!(ctx.accounts.owner_token_ata.mint, vault.mint, NflError::InvalidOutcomeTokenAccount);
+
+        // Return the escrowed tokens, signed by the OrderBook PDA
+        let market_key = ctx.accounts.market.key();
+        let bump = ctx.bumps.order_book;
+        let seeds = &[
+            b"orderbook",
+            market_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_refund = Transfer {
+            from: vault.to_account_info(),
+            to: ctx.accounts.owner_token_ata.to_account_info(),
+            authority: ctx.accounts.order_book.to_account_info(),
+        };
+        token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_refund, signer), refund)?;
+
+        emit_cpi!(order.cancelled(market_key, CancelReason::Owner, refund));
+        Ok(())
+    }
+
+    /// Cancel every resting order owned by the signer.
+    /// Escrowed YES and NO tokens are returned to the owner's YES and NO token accounts,
+    /// and escrowed bid collateral to the owner's collateral account.
+    pub fn cancel_all_orders(ctx: Context<CancelAllOrders>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+
+        // Tally the escrow owed to the owner on each side, then drop their orders
+        let mut yes_refund: u64 = 0;
+        let mut no_refund: u64 = 0;
+        let mut collateral_refund: u64 = 0;
+        let mut cancelled = Vec::new();
+        {
+            let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+            for order in book.orders().iter().filter(|o| o.owner == owner) {
+                let (total, amount) = match order.side() {
+                    Side::Ask if order.is_yes() => (&mut yes_refund, order.quantity),
+                    Side::Ask => (&mut no_refund, order.quantity),
+                    Side::Bid => (&mut collateral_refund, order.escrow),
+                };
+                *total = total.checked_add(amount).ok_or(NflError::MathOverflow)?;
+                cancelled.push((*order, amount));
+            }
+            book.retain(|o| o.owner != owner);
+        }
+
+        let order_book_info = ctx.accounts.order_book.to_account_info();
+        let market_key = ctx.accounts.market.key();
+        let bump = ctx.bumps.order_book;
+        let seeds = &[
+            b"orderbook",
+            market_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        if yes_refund > 0 {
+            let cpi_refund = Transfer {
+                from: ctx.accounts.yes_vault.to_account_info(),
+                to: ctx.accounts.owner_yes_ata.to_account_info(),
+                authority: order_book_info.clone(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_refund, signer), yes_refund)?;
+        }
+        if no_refund > 0 {
+            let cpi_refund = Transfer {
+                from: ctx.accounts.no_vault.to_account_info(),
+                to: ctx.accounts.owner_no_ata.to_account_info(),
+                authority: order_book_info.clone(),
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_refund, signer), no_refund)?;
+        }
+        if collateral_refund > 0 {
+            let cpi_refund = Transfer {
+                from: ctx.accounts.collateral_vault.to_account_info(),
+                to: ctx.accounts.owner_collateral_ata.to_account_info(),
+                authority: order_book_info,
+            };
+            token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_refund, signer), collateral_refund)?;
+        }
+
+        for (order, refund) in cancelled {
+            emit_cpi!(order.cancelled(market_key, CancelReason::Owner, refund));
+        }
+        Ok(())
+    }
+
+    /// Permissionless crank: remove an expired GTT order from the book and return its escrow
+    /// to a token account of the order's owner, so stale quotes do not linger.
+    pub fn purge_expired_order(ctx: Context<PurgeOrder>, order_id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let order = {
+            let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+            let index = book.orders().iter().position(|o| o.id == order_id).ok_or(NflError::OrderNotFound)?;
+            require!(book.orders()[index].is_expired(now), NflError::OrderNotExpired);
+
+            book.remove(index)
+        };
+
+        let refund = refund_purged_order(&ctx, &order)?;
+
+        emit_cpi!(order.cancelled(ctx.accounts.market.key(), CancelReason::Expired, refund));
+        Ok(())
+    }
+
+    /// Permissionless crank: once the market is resolved, remove any resting order from the book
+    /// and return its escrow to a token account of the order's owner, so a maker who never
+    /// cancels cannot keep the order book from being closed.
+    pub fn purge_resolved_order(ctx: Context<PurgeOrder>, order_id: u64) -> Result<()> {
+        require!(ctx.accounts.market.status == MarketStatus::Resolved, NflError::MarketNotResolved);
+
+        let order = {
+            let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+            let index = book.orders().iter().position(|o| o.id == order_id).ok_or(NflError::OrderNotFound)?;
+
+            book.remove(index)
+        };
+
+        let refund = refund_purged_order(&ctx, &order)?;
+
+        emit_cpi!(order.cancelled(ctx.accounts.market.key(), CancelReason::MarketResolved, refund));
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    // DESIGNATED MARKET MAKERS
+    // -------------------------------------------------------------------------
+
+    /// Set up the designated market maker (DMM) program for an order book.
+    /// Defines the quoting obligations registered makers are measured against
+    /// and the benefits they earn while compliant.
+    pub fn initialize_dmm_program(ctx: Context<InitializeDmmProgram>, params: DmmProgramParams) -> Result<()> {
+        require!(params.min_uptime_bps <= BPS_DENOMINATOR, NflError::InvalidDmmConfig);
+        require!(params.rebate_share_bps <= BPS_DENOMINATOR, NflError::InvalidDmmConfig);
+        require!((params.fee_tier as usize) < DMM_FEE_TIER_DISCOUNTS_BPS.len(), NflError::InvalidDmmConfig);
+        require!(params.sample_interval_secs > 0, NflError::InvalidDmmConfig);
+
+        let config = &mut ctx.accounts.dmm_config;
+        config.order_book = ctx.accounts.order_book.key();
+        config.max_spread = params.max_spread;
+        config.min_size = params.min_size;
+        config.min_uptime_bps = params.min_uptime_bps;
+        config.min_samples = params.min_samples;
+        config.sample_interval_secs = params.sample_interval_secs;
+        config.fee_tier = params.fee_tier;
+        config.rebate_share_bps = params.rebate_share_bps;
+
+        msg!(
+            "DMM program initialized: order_book={}, max_spread={}, min_size={}, min_uptime_bps={}",
+            config.order_book,
+            config.max_spread,
+            config.min_size,
+            config.min_uptime_bps
+        );
+        Ok(())
+    }
+
+    /// Register a wallet as a designated market maker on this order book.
+    /// Its first sampling period starts now.
+    pub fn register_market_maker(ctx: Context<RegisterMarketMaker>) -> Result<()> {
+        let next_order_id = ctx.accounts.order_book.load()?.next_order_id;
+        let now = Clock::get()?.unix_timestamp;
+
+        let mm = &mut ctx.accounts.market_maker;
+        mm.order_book = ctx.accounts.order_book.key();
+        mm.maker = ctx.accounts.maker.key();
+        mm.registered_ts = now;
+        mm.samples_total = 0;
+        mm.samples_compliant = 0;
+        mm.last_sample_ts = now;
+        mm.sampled_order_id = next_order_id;
+        mm.benefits_active = false;
+        mm.fee_tier = 0;
+        mm.rebate_share_bps = 0;
+
+        msg!("Market maker registered: maker={}, order_book={}", mm.maker, mm.order_book);
+        Ok(())
+    }
+
+    /// Remove a designated market maker, revoking any benefits and returning the rent to the authority.
+    pub fn deregister_market_maker(ctx: Context<DeregisterMarketMaker>) -> Result<()> {
+        msg!(
+            "Market maker deregistered: maker={}, order_book={}",
+            ctx.accounts.market_maker.maker,
+            ctx.accounts.market_maker.order_book
+        );
+        Ok(())
+    }
+
+    /// Permissionless crank: score a registered maker's resting asks against the obligations for
+    /// every sampling period that has ended since the last call.
+    ///
+    /// Periods are fixed `sample_interval_secs` slots of the clock starting at registration, so the
+    /// caller cannot choose when a sample is taken. Only asks that were already resting at the last
+    /// call count, which proves they stayed on the book for the whole of the elapsed periods: orders
+    /// are never repriced, and a cancelled and requoted order gets a new id. The periods are
+    /// compliant when such asks include a YES ask and a NO ask of at least `min_size` whose combined
+    /// price exceeds PRICE_SCALE by no more than `max_spread`.
+    /// Benefits are granted or revoked from the measured uptime after each call, and the maker's
+    /// resting orders placed with benefits follow suit.
+    pub fn sample_market_maker(ctx: Context<SampleMarketMaker>) -> Result<()> {
+        let config = &ctx.accounts.dmm_config;
+        let mm = &mut ctx.accounts.market_maker;
+
+        let now = Clock::get()?.unix_timestamp;
+        let periods = now.saturating_sub(mm.last_sample_ts) / config.sample_interval_secs;
+        require!(periods > 0, NflError::SampleTooSoon);
+
+        // Best qualifying ask the maker has kept resting since the last sample on each outcome
+        // Asks are sorted cheapest first, so the first match is the best
+        let next_order_id = ctx.accounts.order_book.load()?.next_order_id;
+        let orders = OrderBook::load_orders(&ctx.accounts.order_book)?;
+        let best_ask = |want_yes: bool| {
+            orders[book_segment(&orders, want_yes, Side::Ask)].iter()
+                .find(|o| {
+                    o.owner == mm.maker
+                        && o.id < mm.sampled_order_id
+                        && o.quantity >= config.min_size
+                        && !o.is_expired(now)
+                })
+                .map(|o| o.price)
+        };
+        let yes_ask = best_ask(true);
+        let no_ask = best_ask(false);
+
+        let compliant = match (yes_ask, no_ask) {
+            (Some(yes), Some(no)) => {
+                let spread = yes.saturating_add(no).saturating_sub(PRICE_SCALE);
+                spread <= config.max_spread
+            }
+            _ => false,
+        };
+
+        let periods = periods as u64;
+        mm.samples_total = mm.samples_total.checked_add(periods).ok_or(NflError::MathOverflow)?;
+        if compliant {
+            mm.samples_compliant = mm.samples_compliant.checked_add(periods).ok_or(NflError::MathOverflow)?;
+        }
+        // Stay on the registration-aligned schedule
+        mm.last_sample_ts += periods as i64 * config.sample_interval_secs;
+        mm.sampled_order_id = next_order_id;
+
+        // Grant or revoke benefits from the measured uptime
+        let uptime_bps = mm.uptime_bps();
+        let qualified = mm.samples_total >= config.min_samples && uptime_bps >= config.min_uptime_bps;
+        mm.benefits_active = qualified;
+        mm.fee_tier = if qualified { config.fee_tier } else { 0 };
+        mm.rebate_share_bps = if qualified { config.rebate_share_bps } else { 0 };
+
+        // Orders resting with benefits lose them on revocation and pick up any change of rates
+        drop(orders);
+        OrderBook::load_book_mut(&ctx.accounts.order_book)?.sync_maker_benefits(mm);
+
+        msg!(
+            "Market maker sampled: maker={}, compliant={}, samples={}/{}, uptime_bps={}, benefits_active={}",
+            mm.maker,
+            compliant,
+            mm.samples_compliant,
+            mm.samples_total,
+            uptime_bps,
+            mm.benefits_active
+        );
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    // CLOSING MARKETS
+    // -------------------------------------------------------------------------
+
+    /// Close the order book of a resolved market and its escrow vaults, refunding rent to the
+    /// market authority. Every order must have been cancelled, filled or purged with
+    /// `purge_resolved_order`, and the outcome vaults must be empty; leftover collateral and
+    /// uncollected fees go to the treasury.
+    pub fn close_order_book(ctx: Context<CloseOrderBook>) -> Result<()> {
+        require!(ctx.accounts.market.status == MarketStatus::Resolved, NflError::MarketNotResolved);
+        require!(ctx.accounts.order_book.load()?.order_count == 0, NflError::OrderBookNotEmpty);
+        require!(
+            ctx.accounts.yes_vault.amount == 0 && ctx.accounts.no_vault.amount == 0,
+            NflError::OrderBookNotEmpty
+        );
+
+        let market_key = ctx.accounts.market.key();
+        let bump = ctx.bumps.order_book;
+        let seeds = &[
+            b"orderbook",
+            market_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let mut swept = 0;
+        for vault in [&ctx.accounts.collateral_vault, &ctx.accounts.fee_vault] {
+            if vault.amount > 0 {
+                let cpi_accounts = Transfer {
+                    from: vault.to_account_info(),
+                    to: ctx.accounts.treasury_collateral_ata.to_account_info(),
+                    authority: ctx.accounts.order_book.to_account_info(),
+                };
+                token::transfer(
+                    CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer),
+                    vault.amount,
+                )?;
+                swept += vault.amount;
+            }
+        }
+
+        for vault in [
+            &ctx.accounts.yes_vault,
+            &ctx.accounts.no_vault,
+            &ctx.accounts.collateral_vault,
+            &ctx.accounts.fee_vault,
+        ] {
+            let cpi_accounts = token::CloseAccount {
+                account: vault.to_account_info(),
+                destination: ctx.accounts.authority.to_account_info(),
+                authority: ctx.accounts.order_book.to_account_info(),
+            };
+            token::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            ))?;
+        }
+
+        msg!("Order book closed: Market={}, swept to treasury={}", market_key, swept);
+        Ok(())
+    }
+
+    /// Close a resolved market once every paying token has been redeemed, or after
+    /// `CLOSE_GRACE_PERIOD_SECS`, refunding rent to the market authority. The order book must be
+    /// closed first. Whatever is left in the vault (rounding dust, or collateral nobody redeemed
+    /// within the grace period) goes to the treasury. The YES/NO mints cannot be closed under the
+    /// SPL Token program and are left in place.
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        let market = &ctx.accounts.market;
+        require!(market.status == MarketStatus::Resolved, NflError::MarketNotResolved);
+        require!(ctx.accounts.order_book.data_is_empty(), NflError::OrderBookStillOpen);
+
+        let fully_redeemed = (market.payout_yes_bps == 0 || ctx.accounts.yes_mint.supply == 0)
+            && (market.payout_no_bps == 0 || ctx.accounts.no_mint.supply == 0);
+        let now = Clock::get()?.unix_timestamp;
+        let grace_over = now >= market.resolved_ts.saturating_add(CLOSE_GRACE_PERIOD_SECS);
+        require!(fully_redeemed || grace_over, NflError::MarketNotRedeemed);
+
+        let market_key = market.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"market_auth",
+            market_key.as_ref(),
+            &[market.market_authority_bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[signer_seeds];
+
+        // Markets without a committee resolve through a proposal, which must be closed with them
+        if market.committee.is_empty() {
+            require!(
+                ctx.accounts.proposal.is_some() && ctx.accounts.bond_vault.is_some(),
+                NflError::ProposalAccountsMissing
+            );
+        }
+
+        // Bonds nobody claimed within the grace period are swept like unredeemed collateral
+        let unclaimed_bond = ctx.accounts.bond_vault.as_ref().map_or(0, |vault| vault.amount);
+        require!(unclaimed_bond == 0 || grace_over, NflError::BondNotClaimed);
+
+        let leftover = ctx.accounts.vault.amount;
+        let vaults = [Some(&ctx.accounts.vault), ctx.accounts.bond_vault.as_ref()];
+        for vault in vaults.into_iter().flatten() {
+            if vault.amount > 0 {
+                let transfer_accounts = token::Transfer {
+                    from: vault.to_account_info(),
+                    to: ctx.accounts.treasury_collateral_ata.to_account_info(),
+                    authority: ctx.accounts.market_authority.to_account_info(),
+                };
+                token::transfer(
+                    CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), transfer_accounts, signer_seeds),
+                    vault.amount,
+                )?;
+            }
+
+            let close_accounts = token::CloseAccount {
+                account: vault.to_account_info(),
+                destination: ctx.accounts.authority.to_account_info(),
+                authority: ctx.accounts.market_authority.to_account_info(),
+            };
+            token::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                signer_seeds,
+            ))?;
+        }
+
+        msg!(
+            "Market closed: market={}, leftover to treasury={}, unclaimed bond to treasury={}, fully_redeemed={}",
+            market_key,
+            leftover,
+            unclaimed_bond,
+            fully_redeemed
+        );
+        Ok(())
+    }
+}
+
+// --- Redemption ---
+
+/// Burn the paying sides (capped at `amount` each when given) and pay out their collateral.
+fn redeem_tokens(ctx: Context<Redeem>, amount: Option<u64>) -> Result<()> {
+    let market = &ctx.accounts.market;
+
+    require!(
+        market.status == MarketStatus::Resolved,
+        NflError::MarketNotResolved
+    );
+    require!(
+        market.outcome != Outcome::Pending,
+        NflError::CannotRedeemForOutcome
+    );
+
+    let requested = |balance: u64| amount.map_or(balance, |a| a.min(balance));
+
+    // Only sides that pay out are burned; worthless tokens are left untouched
+    let yes_amount = if market.payout_yes_bps > 0 { requested(ctx.accounts.user_yes_ata.amount) } else { 0 };
+    let no_amount = if market.payout_no_bps > 0 { requested(ctx.accounts.user_no_ata.amount) } else { 0 };
+
+    let payout = pair_payout_for(
+        yes_amount,
+        market.payout_yes_bps,
+        no_amount,
+        market.payout_no_bps,
+    )?;
+
+    require!(payout > 0, NflError::NothingToRedeem);
+
+    // Burn the redeemed tokens
+    for (mint_ai, ata_ai, amount) in [
+        (
+            ctx.accounts.yes_mint.to_account_info(),
+            ctx.accounts.user_yes_ata.to_account_info(),
+            yes_amount,
+        ),
+        (
+            ctx.accounts.no_mint.to_account_info(),
+            ctx.accounts.user_no_ata.to_account_info(),
+            no_amount,
+        ),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let burn_accounts = token::Burn {
+            mint: mint_ai,
+            from: ata_ai,
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+        token::burn(cpi_ctx, amount)?;
+    }
+
+    let recipient = match &ctx.accounts.recipient_collateral_ata {
+        Some(recipient) => recipient.to_account_info(),
+        None => ctx.accounts.user_collateral_ata.to_account_info(),
+    };
+
+    // Transfer collateral from vault to the recipient, signed by PDA
+    let market_key = market.key();
+    let signer_seeds: &[&[u8]] = &[
+        b"market_auth",
+        market_key.as_ref(),
+        &[market.market_authority_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[signer_seeds];
+
+    let transfer_accounts = token::Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: recipient.clone(),
+        authority: ctx.accounts.market_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, payout)?;
+
+    emit_cpi!(Redeemed {
+        market: market.key(),
+        user: ctx.accounts.user.key(),
+        recipient: recipient.key(),
+        yes_amount,
+        no_amount,
+        payout,
+    });
+
+    Ok(())
+}
+
+// --- Order Purging ---
+
+/// Return the escrow of a purged order to `owner_token_ata`, signed by the OrderBook PDA:
+/// outcome tokens for an ask, collateral for a bid. Returns the amount refunded.
+fn refund_purged_order(ctx: &Context<PurgeOrder>, order: &Order) -> Result<u64> {
+    let (vault, refund) = match order.side() {
+        Side::Ask if order.is_yes() => (&ctx.accounts.yes_vault, order.quantity),
+        Side::Ask => (&ctx.accounts.no_vault, order.quantity),
+        Side::Bid => (&ctx.accounts.collateral_vault, order.escrow),
+    };
+    require_keys_eq!(ctx.accounts.owner_token_ata.owner, order.owner, NflError::NotOrderOwner);
+    require_keys_eq!(ctx.accounts.owner_token_ata.mint, vault.mint, NflError::InvalidOutcomeTokenAccount);
+
+    let market_key = ctx.accounts.market.key();
+    let bump = ctx.bumps.order_book;
+    let seeds = &[
+        b"orderbook",
+        market_key.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_refund = Transfer {
+        from: vault.to_account_info(),
+        to: ctx.accounts.owner_token_ata.to_account_info(),
+        authority: ctx.accounts.order_book.to_account_info(),
+    };
+    token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_refund, signer), refund)?;
+
+    Ok(refund)
+}
+
+// --- Order Matching ---
+
+/// Take liquidity from the asks: fill up to `quantity` and settle with the buyer.
+/// With a `max_price`, the whole quantity must fill at or below it. Asks priced above
+/// `OrderBook::pair_price_cap` are never taken, whatever the `max_price`.
+fn execute_buy<'info>(
+    ctx: Context<'_, '_, '_, 'info, MarketBuyAccounts<'info>>,
+    quantity: u64,
+    want_yes: bool,
+    max_price: Option<u64>,
+    self_trade: SelfTradePrevention,
+) -> Result<()> {
+    ctx.accounts.market.require_trading_open()?;
+    ctx.accounts.order_book.load()?.require_valid_order(None, quantity)?;
+
+    // Match against the book first; the borrow must be released before any CPI
+    let now = Clock::get()?.unix_timestamp;
+    // Never pay more than PRICE_SCALE minus the best ask on the other outcome
+    let cap = OrderBook::pair_price_cap(&ctx.accounts.order_book, want_yes, now)?;
+    let limit = max_price.map_or(cap, |max_price| max_price.min(cap));
+    let matched = {
+        let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+        let buyer = ctx.accounts.buyer.key();
+        book.match_orders(Side::Ask, want_yes, quantity, Some(limit), max_price.is_some(), buyer, self_trade, now)?
+    };
+
+    // Prepare PDA signer seeds (needed to unlock tokens from the Vault)
+    let market_key = ctx.accounts.market.key();
+    let bump = ctx.bumps.order_book;
+    let seeds = &[
+        b"orderbook",
+        market_key.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let vault = if want_yes { &ctx.accounts.yes_vault } else { &ctx.accounts.no_vault };
+    TakerAccounts {
+        taker: ctx.accounts.buyer.to_account_info(),
+        taker_pay_ata: ctx.accounts.buyer_collateral_ata.to_account_info(),
+        taker_receive_ata: ctx.accounts.buyer_receive_token_ata.to_account_info(),
+        escrow_vault: vault.to_account_info(),
+        fee_vault: ctx.accounts.fee_vault.to_account_info(),
+        order_book: ctx.accounts.order_book.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        makers: ctx.remaining_accounts,
+    }
+    .settle_buy(&matched, signer)?;
+
+    for fill in matched.fills.iter() {
+        emit_cpi!(fill.event(market_key, ctx.accounts.buyer.key(), want_yes, Side::Ask));
+    }
+    Ok(())
+}
+
+/// Take liquidity from the bids: fill up to `quantity` and settle with the seller.
+/// With a `min_price`, the whole quantity must fill at or above it.
+fn execute_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, MarketSellAccounts<'info>>,
+    quantity: u64,
+    sell_yes: bool,
+    min_price: Option<u64>,
+    self_trade: SelfTradePrevention,
+) -> Result<()> {
+    ctx.accounts.market.require_trading_open()?;
+    ctx.accounts.order_book.load()?.require_valid_order(None, quantity)?;
+
+    let market = &ctx.accounts.market;
+    let outcome_mint = if sell_yes { market.yes_mint } else { market.no_mint };
+    require_keys_eq!(
+        ctx.accounts.seller_token_ata.mint,
+        outcome_mint,
+        NflError::InvalidOutcomeTokenAccount
+    );
+
+    // Match against the book first; the borrow must be released before any CPI
+    let now = Clock::get()?.unix_timestamp;
+    let matched = {
+        let mut book = OrderBook::load_book_mut(&ctx.accounts.order_book)?;
+        let seller = ctx.accounts.seller.key();
+        book.match_orders(Side::Bid, sell_yes, quantity, min_price, min_price.is_some(), seller, self_trade, now)?
+    };
+
+    // Prepare PDA signer seeds (needed to release collateral from the vault)
+    let market_key = ctx.accounts.market.key();
+    let bump = ctx.bumps.order_book;
+    let seeds = &[
+        b"orderbook",
+        market_key.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    TakerAccounts {
+        taker: ctx.accounts.seller.to_account_info(),
+        taker_pay_ata: ctx.accounts.seller_token_ata.to_account_info(),
+        taker_receive_ata: ctx.accounts.seller_receive_collateral_ata.to_account_info(),
+        escrow_vault: ctx.accounts.collateral_vault.to_account_info(),
+        fee_vault: ctx.accounts.fee_vault.to_account_info(),
+        order_book: ctx.accounts.order_book.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        makers: ctx.remaining_accounts,
+    }
+    .settle_sell(&matched, signer)?;
+
+    for fill in matched.fills.iter() {
+        emit_cpi!(fill.event(market_key, ctx.accounts.seller.key(), sell_yes, Side::Bid));
+    }
+    Ok(())
+}
+
+/// Accounts a taker settles its fills with, shared by the market orders and IOC / FOK limit orders.
+struct TakerAccounts<'a, 'info> {
+    taker: AccountInfo<'info>,
+    /// Collateral account of a buyer, outcome token account of a seller
+    taker_pay_ata: AccountInfo<'info>,
+    /// Outcome token account of a buyer, collateral account of a seller
+    taker_receive_ata: AccountInfo<'info>,
+    /// Vault holding the makers' escrow: an outcome vault for asks, the collateral vault for bids
+    escrow_vault: AccountInfo<'info>,
+    fee_vault: AccountInfo<'info>,
+    order_book: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    /// One maker account per fill, in fill order, passed in 'remaining_accounts'
+    makers: &'a [AccountInfo<'info>],
+}
+
+impl<'info> TakerAccounts<'_, 'info> {
+    /// Maker account for the `index`-th fill, checked against the order's receive account.
+    fn maker_account(&self, index: usize, fill: &Fill) -> Result<AccountInfo<'info>> {
+        let maker_account = self.makers.get(index).ok_or(NflError::MissingSellerAccounts)?;
+
+        // SECURITY CHECK: Ensure the account passed matches the order's receive account
+        if maker_account.key() != fill.maker_receive_ata {
+            return err!(NflError::SellerAccountMismatch);
+        }
+        Ok(maker_account.clone())
+    }
+
+    /// Settle fills against asks: the buyer pays each seller and the fees, and the vault releases
+    /// the filled outcome tokens along with any self-trade refund. `signer` holds the OrderBook PDA seeds.
+    fn settle_buy(&self, matched: &Matched, signer: &[&[&[u8]]]) -> Result<()> {
+        let mut filled: u64 = matched.refund;
+        let mut fees: u64 = 0;
+        for (index, fill) in matched.fills.iter().enumerate() {
+            // Payment Transfer: Buyer pays Seller (Collateral/USDC) directly,
+            // net of the maker fee (or plus the maker rebate)
+            let maker_proceeds = apply_signed(fill.cost, -fill.maker_fee)?;
+            let cpi_pay = Transfer {
+                from: self.taker_pay_ata.clone(),
+                to: self.maker_account(index, fill)?,
+                authority: self.taker.clone(),
+            };
+            token::transfer(CpiContext::new(self.token_program.clone(), cpi_pay), maker_proceeds)?;
+
+            filled += fill.quantity;
+            fees = fees.checked_add(fill.net_fee()?).ok_or(NflError::MathOverflow)?;
+        }
+
+        // Fee Transfer: Buyer pays the taker fee plus the net maker fee into the fee vault
+        if fees > 0 {
+            let cpi_fee = Transfer {
+                from: self.taker_pay_ata.clone(),
+                to: self.fee_vault.clone(),
+                authority: self.taker.clone(),
+            };
+            token::transfer(CpiContext::new(self.token_program.clone(), cpi_fee), fees)?;
+        }
+
+        // Asset Transfer: Vault releases all filled (and self-trade cancelled) Outcome Tokens to Buyer
+        // Signed by the OrderBook PDA
+        if filled > 0 {
+            let cpi_receive = Transfer {
+                from: self.escrow_vault.clone(),
+                to: self.taker_receive_ata.clone(),
+                authority: self.order_book.clone(),
+            };
+            token::transfer(CpiContext::new_with_signer(self.token_program.clone(), cpi_receive, signer), filled)?;
+        }
+
+        Ok(())
+    }
+
+    /// Settle fills against bids: the seller delivers outcome tokens to each bidder, and the
+    /// collateral vault releases the bids' escrow to the seller and the fee vault, along with
+    /// any self-trade refund.
+    fn settle_sell(&self, matched: &Matched, signer: &[&[&[u8]]]) -> Result<()> {
+        let mut proceeds: u64 = matched.refund;
+        let mut fees: u64 = 0;
+        for (index, fill) in matched.fills.iter().enumerate() {
+            // Asset Transfer: Seller delivers Outcome Tokens to the Bidder directly
+            let cpi_deliver = Transfer {
+                from: self.taker_pay_ata.clone(),
+                to: self.maker_account(index, fill)?,
+                authority: self.taker.clone(),
+            };
+            token::transfer(CpiContext::new(self.token_program.clone(), cpi_deliver), fill.quantity)?;
+
+            // The bid's escrow covers the cost and its maker fee; the seller gets it net of all fees
+            let fee = fill.net_fee()?;
+            let seller_proceeds = fill.escrow.checked_sub(fee).ok_or(NflError::MathOverflow)?;
+            proceeds = proceeds.checked_add(seller_proceeds).ok_or(NflError::MathOverflow)?;
+            fees = fees.checked_add(fee).ok_or(NflError::MathOverflow)?;
+        }
+
+        // Payment Transfer: Collateral vault releases the Bidders' escrow to the Seller
+        // Signed by the OrderBook PDA
+        if proceeds > 0 {
+            let cpi_pay = Transfer {
+                from: self.escrow_vault.clone(),
+                to: self.taker_receive_ata.clone(),
+                authority: self.order_book.clone(),
+            };
+            token::transfer(CpiContext::new_with_signer(self.token_program.clone(), cpi_pay, signer), proceeds)?;
+        }
+
+        // Fee Transfer: Collateral vault moves the fees into the fee vault
+        if fees > 0 {
+            let cpi_fee = Transfer {
+                from: self.escrow_vault.clone(),
+                to: self.fee_vault.clone(),
+                authority: self.order_book.clone(),
+            };
+            token::transfer(CpiContext::new_with_signer(self.token_program.clone(), cpi_fee, signer), fees)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Collateral owed for `quantity` tokens at `price`, rounded up so a buyer never pays less than
+/// the quoted price: ceil(price * quantity / PRICE_SCALE)
+fn order_cost(price: u64, quantity: u64) -> Result<u64> {
+    let cost = (price as u128)
+        .checked_mul(quantity as u128)
+        .ok_or(NflError::MathOverflow)?
+        .div_ceil(PRICE_SCALE as u128);
+    u64::try_from(cost).map_err(|_| error!(NflError::MathOverflow))
+}
+
+/// Maker fee on `cost` at a signed rate, rounded down in magnitude; negative values are rebates.
+fn maker_fee_for(cost: u64, maker_fee_bps: i16) -> Result<i64> {
+    let fee = payout_for(cost, maker_fee_bps.unsigned_abs())? as i64;
+    Ok(if maker_fee_bps < 0 { -fee } else { fee })
+}
+
+/// Taker fee on `cost`, rounded up so no fill trades for free.
+fn taker_fee_for(cost: u64, taker_fee_bps: u16) -> Result<u64> {
+    let fee = (cost as u128)
+        .checked_mul(taker_fee_bps as u128)
+        .ok_or(NflError::MathOverflow)?
+        .div_ceil(BPS_DENOMINATOR as u128);
+    u64::try_from(fee).map_err(|_| error!(NflError::MathOverflow))
+}
+
+/// Signed maker fee rate after designated market maker benefits: `fee_tier` discounts a positive
+/// fee, and `rebate_share_bps` of the taker fee is rebated to the maker. The rebate never exceeds
+/// the taker fee that funds it.
+pub fn dmm_maker_fee_bps(maker_fee_bps: i16, taker_fee_bps: u16, fee_tier: u8, rebate_share_bps: u16) -> i16 {
+    let denominator = BPS_DENOMINATOR as i32;
+    let discount = DMM_FEE_TIER_DISCOUNTS_BPS.get(fee_tier as usize).copied().unwrap_or(0) as i32;
+    let mut rate = maker_fee_bps as i32;
+    if rate > 0 {
+        rate = rate * (denominator - discount) / denominator;
+    }
+    rate -= taker_fee_bps as i32 * rebate_share_bps as i32 / denominator;
+    rate.max(-(taker_fee_bps as i32)) as i16
+}
+
+/// `amount + delta`, failing if the result leaves the u64 range.
+fn apply_signed(amount: u64, delta: i64) -> Result<u64> {
+    u64::try_from(amount as i128 + delta as i128).map_err(|_| error!(NflError::MathOverflow))
+}
+
+/// Result of matching a taker against the book.
+struct Matched {
+    fills: Vec<Fill>,
+    /// Quantity neither filled nor removed by self-trade prevention
+    remaining: u64,
+    /// Escrow of the taker's own orders cancelled or decremented by self-trade prevention.
+    /// It sits in the vault that releases the fills, so it is returned to the taker with them.
+    refund: u64,
+    /// Self-trade prevention cancelled the rest of the taker's order
+    taker_cancelled: bool,
+}
+
+/// One resting order matched against a taker.
+/// Collected while the book is borrowed and settled once it is released.
+struct Fill {
+    order_id: u64,
+    maker: Pubkey,
+    maker_receive_ata: Pubkey,
+    price: u64,
+    quantity: u64,
+    /// Collateral released from a bid's escrow (always 0 for asks)
+    escrow: u64,
+    /// Notional value of the fill: (price * quantity) / PRICE_SCALE
+    cost: u64,
+    /// Fee charged to the taker
+    taker_fee: u64,
+    /// Fee charged to the maker; negative for a rebate
+    maker_fee: i64,
+}
+
+impl Fill {
+    /// Collateral kept by the fee vault: the taker fee plus the signed maker fee.
+    fn net_fee(&self) -> Result<u64> {
+        apply_signed(self.taker_fee, self.maker_fee)
+    }
+
+    /// The `OrderFilled` event for this fill against a resting order on `maker_side`.
+    fn event(&self, market: Pubkey, taker: Pubkey, is_yes: bool, maker_side: Side) -> OrderFilled {
+        OrderFilled {
+            market,
+            order_id: self.order_id,
+            maker: self.maker,
+            taker,
+            maker_side,
+            is_yes,
+            price: self.price,
+            quantity: self.quantity,
+            cost: self.cost,
+            taker_fee: self.taker_fee,
+            maker_fee: self.maker_fee,
+        }
+    }
+}
+
+// --- Markets ---
+
+/// Initialize a binary or scalar market created through `CreateMarket`, along with its metadata.
+fn init_market(
+    ctx: Context<CreateMarket>,
+    expiry_ts: i64,
+    trading_window: TradingWindow,
+    kind: MarketKind,
+    metadata: MarketMetadataParams,
+    resolution: ResolutionParams,
+) -> Result<()> {
+    require!(expiry_ts > 0, NflError::InvalidExpiry);
+    require!(trading_window.open_ts < trading_window.close_ts, NflError::InvalidTradingWindow);
+    require!(trading_window.close_ts <= expiry_ts, NflError::InvalidTradingWindow);
+    require!(resolution.challenge_period_secs >= 0, NflError::InvalidResolutionConfig);
+    validate_committee(&resolution.committee, resolution.committee_threshold)?;
+    require!(!metadata.event_id.is_empty(), NflError::InvalidEventId);
+
+    ctx.accounts.metadata.market = ctx.accounts.market.key();
+    ctx.accounts.metadata.set(metadata)?;
+
+    let market = &mut ctx.accounts.market;
+
+    market.authority = ctx.accounts.authority.key();
+    market.treasury = ctx.accounts.authority.key();
+    market.resolver = resolution.resolver;
+    market.escalation_resolver = resolution.escalation_resolver;
+    market.challenge_period_secs = resolution.challenge_period_secs;
+    market.resolution_bond = resolution.bond;
+    market.committee_votes = vec![None; resolution.committee.len()];
+    market.committee = resolution.committee;
+    market.committee_threshold = resolution.committee_threshold;
+    market.base_mint = ctx.accounts.base_mint.key();
+    market.yes_mint = ctx.accounts.yes_mint.key();
+    market.no_mint = ctx.accounts.no_mint.key();
+    market.vault = ctx.accounts.vault.key();
+    market.expiry_ts = expiry_ts;
+    market.trading_open_ts = trading_window.open_ts;
+    market.trading_close_ts = trading_window.close_ts;
+    market.status = MarketStatus::Open;
+    market.outcome = Outcome::Pending;
+    market.market_authority_bump = ctx.bumps.market_authority;
+    market.payout_yes_bps = 0;
+    market.payout_no_bps = 0;
+    market.kind = kind;
+
+    emit_cpi!(MarketCreated {
+        market: market.key(),
+        authority: market.authority,
+        event_id: ctx.accounts.metadata.event_id.clone(),
+        kind,
+        resolver: market.resolver,
+        base_mint: market.base_mint,
+        yes_mint: market.yes_mint,
+        no_mint: market.no_mint,
+        vault: market.vault,
+        expiry_ts,
+        trading_open_ts: market.trading_open_ts,
+        trading_close_ts: market.trading_close_ts,
+    });
+
+    Ok(())
+}
+
+/// Check a resolution committee: empty, or up to `MAX_COMMITTEE_SIZE` distinct members
+/// with a threshold between a strict majority and all of them.
+fn validate_committee(committee: &[Pubkey], threshold: u8) -> Result<()> {
+    if committee.is_empty() {
+        return Ok(());
+    }
+    require!(committee.len() <= Market::MAX_COMMITTEE_SIZE, NflError::InvalidCommittee);
+    require!(
+        (threshold as usize) * 2 > committee.len() && (threshold as usize) <= committee.len(),
+        NflError::InvalidCommittee
+    );
+    for (i, member) in committee.iter().enumerate() {
+        require!(!committee[..i].contains(member), NflError::InvalidCommittee);
+    }
+    Ok(())
+}
+
+/// Record the final outcome of a market and the payout split it implies.
+/// Returns the `MarketResolved` event for the caller to emit.
+fn settle_market(market: &mut Account<Market>, outcome: Outcome) -> Result<MarketResolved> {
+    let (payout_yes_bps, payout_no_bps) =
+        market.payout_split(outcome).ok_or(NflError::InvalidResolutionOutcome)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    market.status = MarketStatus::Resolved;
+    market.outcome = outcome;
+    market.payout_yes_bps = payout_yes_bps;
+    market.payout_no_bps = payout_no_bps;
+    market.resolved_ts = now;
+
+    Ok(MarketResolved {
+        market: market.key(),
+        outcome,
+        payout_yes_bps,
+        payout_no_bps,
+        resolved_ts: now,
+    })
+}
+
+/// Pay `amount` out of a market's bond vault, signed by the market authority PDA.
+fn pay_bond<'info>(
+    market: &Account<'info, Market>,
+    bond_vault: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    market_authority: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let market_key = market.key();
+    let signer_seeds: &[&[u8]] = &[
+        b"market_auth",
+        market_key.as_ref(),
+        &[market.market_authority_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[signer_seeds];
+
+    let transfer_accounts = token::Transfer {
+        from: bond_vault.to_account_info(),
+        to: to.to_account_info(),
+        authority: market_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), transfer_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+// --- Categorical Markets ---
+
+/// Pair up the (outcome mint, user token account) accounts of a complete set,
+/// one pair per outcome in index order, checking each against the market and the user.
+fn outcome_set_accounts<'info>(
+    market: &CategoricalMarket,
+    user: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<(AccountInfo<'info>, AccountInfo<'info>)>> {
+    require!(
+        market.outcome_mints.len() == market.num_outcomes as usize,
+        NflError::OutcomeMintsIncomplete
+    );
+    require!(
+        accounts.len() == 2 * market.outcome_mints.len(),
+        NflError::MissingOutcomeAccounts
+    );
+
+    accounts
+        .chunks_exact(2)
+        .zip(market.outcome_mints.iter())
+        .map(|(pair, outcome_mint)| {
+            let (mint_info, ata_info) = (&pair[0], &pair[1]);
+            require_keys_eq!(mint_info.key(), *outcome_mint, NflError::InvalidOutcomeMint);
+
+            let user_ata = Account::<TokenAccount>::try_from(ata_info)?;
+            require_keys_eq!(user_ata.mint, *outcome_mint, NflError::InvalidOutcomeTokenAccount);
+            require_keys_eq!(user_ata.owner, *user, NflError::InvalidOutcomeTokenAccount);
+
+            Ok((mint_info.clone(), ata_info.clone()))
+        })
+        .collect()
+}
+
+// --- Accounts ---
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(event: MarketMetadataParams)]
+pub struct CreateMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// One market per (authority, event_id); the event id names a single proposition on a game,
+    /// so moneyline, spread and total markets on the same game use different ids
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Market::SIZE,
+        seeds = [b"market", authority.key().as_ref(), event.event_id.as_bytes()],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+        mint::decimals = base_mint.decimals,
+        mint::authority = market_authority
+    )]
+    pub yes_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+        mint::decimals = base_mint.decimals,
+        mint::authority = market_authority
+    )]
+    pub no_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MarketMetadata::SIZE,
+        seeds = [b"market_metadata", market.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, MarketMetadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOutcomeTokenMetadata<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        has_one = yes_mint,
+        has_one = no_mint
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"market_metadata", market.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, MarketMetadata>,
+
+    pub yes_mint: Account<'info, Mint>,
+
+    pub no_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), yes_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    /// CHECK: Metaplex metadata PDA for the YES mint, created by the token metadata program.
+    pub yes_token_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), no_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    /// CHECK: Metaplex metadata PDA for the NO mint, created by the token metadata program.
+    pub no_token_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, TokenMetadata>,
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketMetadata<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"market_metadata", market.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, MarketMetadata>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateCategoricalMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CategoricalMarket::SIZE
+    )]
+    pub market: Account<'info, CategoricalMarket>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct AddOutcomeMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = base_mint
+    )]
+    pub market: Account<'info, CategoricalMarket>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"outcome_mint", market.key().as_ref(), &[index]],
+        bump,
+        mint::decimals = base_mint.decimals,
+        mint::authority = market_authority
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Accounts for minting or merging complete sets of a categorical market.
+/// The outcome mints and the user's outcome token accounts follow in `remaining_accounts`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompleteSets<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_ata.owner == user.key(),
+        constraint = user_collateral_ata.mint == market.base_mint
+    )]
+    pub user_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(has_one = vault)]
+    pub market: Account<'info, CategoricalMarket>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveCategoricalMarket<'info> {
+    pub resolver: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = resolver
+    )]
+    pub market: Account<'info, CategoricalMarket>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateCategoricalMarketStatus<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub market: Account<'info, CategoricalMarket>,
+}
+
+#[derive(Accounts)]
+pub struct SetCategoricalResolver<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub market: Account<'info, CategoricalMarket>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemCategorical<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(has_one = vault)]
+    pub market: Account<'info, CategoricalMarket>,
+
+    /// Mint of the winning outcome
+    #[account(mut)]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_ata.owner == user.key(),
+        constraint = user_collateral_ata.mint == market.base_mint,
+    )]
+    pub user_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_outcome_ata.owner == user.key(),
+        constraint = user_outcome_ata.mint == outcome_mint.key(),
+    )]
+    pub user_outcome_ata: Account<'info, TokenAccount>,
+
+    /// Optional settlement account for the payout; defaults to `user_collateral_ata`
+    #[account(
+        mut,
+        constraint = recipient_collateral_ata.mint == market.base_mint,
+    )]
+    pub recipient_collateral_ata: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(capacity: u64)]
+pub struct InitializeOrderBook<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init, 
+        payer = authority, 
+        space = OrderBook::space(capacity as usize),
+        seeds = [b"orderbook", market.key().as_ref()], 
+        bump
+    )]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        has_one = authority,
+        has_one = yes_mint,
+        has_one = no_mint
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(constraint = base_mint.key() == market.base_mint @ NflError::InvalidBaseMint)]
+    pub base_mint: Account<'info, Mint>,
+    pub yes_mint: Account<'info, Mint>,
+    pub no_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = yes_mint,
+        token::authority = order_book,
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = no_mint,
+        token::authority = order_book,
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    /// Holds the collateral escrowed by resting bids.
+    #[account(
+        init,
+        payer = authority,
+        token::mint = base_mint,
+        token::authority = order_book,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    /// Collects trading fees in the collateral mint.
+    #[account(
+        init,
+        payer = authority,
+        token::mint = base_mint,
+        token::authority = order_book,
+        seeds = [b"fee_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct GrowOrderBook<'info> {
+    /// Funds the rent for the new order slots
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOrderBookGrid<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = recipient_collateral_ata.mint == market.base_mint @ NflError::InvalidBaseMint)]
+    pub recipient_collateral_ata: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MintPairs<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_ata.owner == user.key(),
+        constraint = user_collateral_ata.mint == market.base_mint
+    )]
+    pub user_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = base_mint,
+        has_one = yes_mint,
+        has_one = no_mint,
+        has_one = vault
+    )]
+    pub market: Account<'info, Market>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub yes_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub no_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_yes_ata.owner == user.key(),
+        constraint = user_yes_ata.mint == yes_mint.key()
+    )]
+    pub user_yes_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_no_ata.owner == user.key(),
+        constraint = user_no_ata.mint == no_mint.key()
+    )]
+    pub user_no_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MergePairs<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_ata.owner == user.key(),
+        constraint = user_collateral_ata.mint == market.base_mint
+    )]
+    pub user_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        has_one = yes_mint,
+        has_one = no_mint,
+        has_one = vault
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub yes_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub no_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_yes_ata.owner == user.key(),
+        constraint = user_yes_ata.mint == yes_mint.key()
+    )]
+    pub user_yes_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_no_ata.owner == user.key(),
+        constraint = user_no_ata.mint == no_mint.key()
+    )]
+    pub user_no_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PlaceLimitSell<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller_token_ata: Account<'info, TokenAccount>,
+    
+    #[account(mut, constraint = seller_receive_collateral_ata.mint == market.base_mint)]
+    pub seller_receive_collateral_ata: Account<'info, TokenAccount>,
+    
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        mut, 
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut, 
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    /// Releases bid escrow to the seller when an IOC or FOK order fills
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+    
+    pub market: Account<'info, Market>,
+    pub token_program: Program<'info, Token>,
+
+    /// The seller's designated market maker account, if any, for its benefits
+    #[account(seeds = [b"market_maker", order_book.key().as_ref(), seller.key().as_ref()], bump)]
+    pub market_maker: Option<Account<'info, MarketMaker>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PlaceLimitBuy<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut, constraint = buyer_collateral_ata.mint == market.base_mint)]
+    pub buyer_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer_receive_token_ata: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    /// Release asks' escrow to the buyer when an IOC or FOK order fills
+    #[account(
+        mut,
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub market: Account<'info, Market>,
+    pub token_program: Program<'info, Token>,
+
+    /// The buyer's designated market maker account, if any, for its benefits
+    #[account(seeds = [b"market_maker", order_book.key().as_ref(), buyer.key().as_ref()], bump)]
+    pub market_maker: Option<Account<'info, MarketMaker>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MarketBuyAccounts<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut, constraint = buyer_collateral_ata.mint == market.base_mint)]
+    pub buyer_collateral_ata: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub buyer_receive_token_ata: Account<'info, TokenAccount>,
+    
+    pub market: Account<'info, Market>,
+    
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        mut, 
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut, 
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MarketSellAccounts<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(mut)]
+    pub seller_token_ata: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = seller_receive_collateral_ata.mint == market.base_mint)]
+    pub seller_receive_collateral_ata: Account<'info, TokenAccount>,
+
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    pub owner: Signer<'info>,
+
+    /// Receives the escrow: the order's outcome token account for an ask,
+    /// a collateral account for a bid.
+    #[account(mut, constraint = owner_token_ata.owner == owner.key())]
+    pub owner_token_ata: Account<'info, TokenAccount>,
+
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PurgeOrder<'info> {
+    /// Receives the escrow; must be owned by the order's owner
+    #[account(mut)]
+    pub owner_token_ata: Account<'info, TokenAccount>,
+
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAllOrders<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = owner_yes_ata.owner == owner.key(),
+        constraint = owner_yes_ata.mint == market.yes_mint @ NflError::InvalidOutcomeTokenAccount
+    )]
+    pub owner_yes_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_no_ata.owner == owner.key(),
+        constraint = owner_no_ata.mint == market.no_mint @ NflError::InvalidOutcomeTokenAccount
+    )]
+    pub owner_no_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_collateral_ata.owner == owner.key(),
+        constraint = owner_collateral_ata.mint == market.base_mint @ NflError::InvalidBaseMint
+    )]
+    pub owner_collateral_ata: Account<'info, TokenAccount>,
+
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"yes_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub yes_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"no_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral_vault", order_book.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDmmProgram<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DmmConfig::SIZE,
+        seeds = [b"dmm_config", order_book.key().as_ref()],
+        bump
+    )]
+    pub dmm_config: Account<'info, DmmConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMarketMaker<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(seeds = [b"dmm_config", order_book.key().as_ref()], bump)]
+    pub dmm_config: Account<'info, DmmConfig>,
+
+    /// CHECK: Wallet being designated; only its address is recorded.
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MarketMaker::SIZE,
+        seeds = [b"market_maker", order_book.key().as_ref(), maker.key().as_ref()],
+        bump
+    )]
+    pub market_maker: Account<'info, MarketMaker>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterMarketMaker<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"market_maker", order_book.key().as_ref(), market_maker.maker.as_ref()],
+        bump
+    )]
+    pub market_maker: Account<'info, MarketMaker>,
+}
+
+#[derive(Accounts)]
+pub struct SampleMarketMaker<'info> {
+    #[account(mut)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(seeds = [b"dmm_config", order_book.key().as_ref()], bump)]
+    pub dmm_config: Account<'info, DmmConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"market_maker", order_book.key().as_ref(), market_maker.maker.as_ref()],
+        bump
+    )]
+    pub market_maker: Account<'info, MarketMaker>,
+}
+
+#[account]
+pub struct Market {
+    pub authority: Pubkey,
+    /// Key allowed to propose the outcome (e.g. an oracle's hot key)
+    pub resolver: Pubkey,
+    /// Key that rules on disputed proposals
+    pub escalation_resolver: Pubkey,
+    /// Seconds after a proposal during which it can be disputed
+    pub challenge_period_secs: i64,
+    /// Collateral bond posted by a proposer and by a disputer
+    pub resolution_bond: u64,
+    /// Resolution committee; empty unless the market resolves by committee vote
+    pub committee: Vec<Pubkey>,
+    /// Number of agreeing votes needed to resolve
+    pub committee_threshold: u8,
+    /// Each member's vote, aligned with `committee`
+    pub committee_votes: Vec<Option<Outcome>>,
+    pub base_mint: Pubkey,
+    pub yes_mint: Pubkey,
+    pub no_mint: Pubkey,
+    pub vault: Pubkey,
+    pub expiry_ts: i64,
+    /// Minting and trading open at this time...
+    pub trading_open_ts: i64,
+    /// ...and close at this time (e.g. kickoff)
+    pub trading_close_ts: i64,
+    pub status: MarketStatus,
+    pub outcome: Outcome,
+    pub market_authority_bump: u8,
+    /// Collateral paid per YES token at resolution, in basis points
+    pub payout_yes_bps: u16,
+    /// Collateral paid per NO token at resolution, in basis points
+    pub payout_no_bps: u16,
+    /// Binary, or scalar with the range LONG (YES) and SHORT (NO) are settled over
+    pub kind: MarketKind,
+    /// Wallet receiving leftover collateral when the market is closed
+    pub treasury: Pubkey,
+    /// When the market was resolved; starts the grace period for closing it
+    pub resolved_ts: i64,
+}
+
+impl Market {
+    pub const MAX_COMMITTEE_SIZE: usize = 9;
+
+    pub const SIZE: usize = 
+          32   // authority
+        + 32   // resolver
+        + 32   // escalation_resolver
+        + 8    // challenge_period_secs
+        + 8    // resolution_bond
+        + 4 + 32 * Self::MAX_COMMITTEE_SIZE   // committee
+        + 1    // committee_threshold
+        + 4 + (1 + 1 + 8) * Self::MAX_COMMITTEE_SIZE   // committee_votes
+        + 32   // base_mint
+        + 32   // yes_mint
+        + 32   // no_mint
+        + 32   // vault
+        + 8    // expiry_ts
+        + 8    // trading_open_ts
+        + 8    // trading_close_ts
+        + 1    // status
+        + 1 + 8   // outcome
+        + 1    // market_authority_bump
+        + 2    // payout_yes_bps
+        + 2    // payout_no_bps
+        + 1 + 8 + 8   // kind
+        + 32   // treasury
+        + 8    // resolved_ts
+    ;
+
+    /// Check that the market is open and the clock is inside its trading window.
+    pub fn require_trading_open(&self) -> Result<()> {
+        require_trading_open(self.status, self.trading_open_ts, self.trading_close_ts)
+    }
+
+    /// Payout split (yes_bps, no_bps) for resolving this market to `outcome`,
+    /// or None if the outcome does not apply to this kind of market.
+    pub fn payout_split(&self, outcome: Outcome) -> Option<(u16, u16)> {
+        match (self.kind, outcome) {
+            (MarketKind::Scalar { lower, upper }, Outcome::Scalar(value)) => {
+                Some(scalar_payout_split(lower, upper, value))
+            }
+            (MarketKind::Scalar { .. }, Outcome::Invalid) | (MarketKind::Binary, _) => outcome.payout_split(),
+            (MarketKind::Scalar { .. }, _) => None,
+        }
+    }
+}
+
+/// A proposed outcome awaiting its challenge period or an escalation ruling.
+/// PDA seeds: ["resolution_proposal", market]. Bonds are held in the ["bond_vault", market] PDA.
+#[account]
+pub struct ResolutionProposal {
+    pub market: Pubkey,
+    pub proposer: Pubkey,
+    pub outcome: Outcome,
+    /// Bond posted by each side
+    pub bond: u64,
+    /// The proposal can be disputed until this time, and finalized from it
+    pub challenge_ends_ts: i64,
+    pub disputer: Option<Pubkey>,
+    /// Set once the market resolves: the only key that may `claim_bond`
+    pub bond_winner: Option<Pubkey>,
+}
+
+impl ResolutionProposal {
+    pub const SIZE: usize =
+          32   // market
+        + 32   // proposer
+        + 1 + 8   // outcome
+        + 8    // bond
+        + 8    // challenge_ends_ts
+        + 1 + 32  // disputer
+        + 1 + 32  // bond_winner
+    ;
+}
+
+/// On-chain description of the game or proposition a market is for.
+/// PDA seeds: ["market_metadata", market].
+#[account]
+pub struct MarketMetadata {
+    pub market: Pubkey,
+    /// Human-readable proposition, e.g. "KC @ BUF: Chiefs win"
+    pub title: String,
+    /// Resolution criteria text, or a URI to the full rules
+    pub resolution_criteria: String,
+    /// SHA-256 of the full resolution rules (zeroes if not used)
+    pub resolution_criteria_hash: [u8; 32],
+    /// e.g. "moneyline", "spread", "total", "futures"
+    pub category: String,
+    /// Key of the proposition this market trades: the NFL game id plus the market on it,
+    /// e.g. "2023_01_KC_BUF_ML" or "2023_01_KC_BUF_SPR_-3.5". It seeds the market's address.
+    pub event_id: String,
+    /// Team codes, e.g. "BUF" and "KC"
+    pub home_team: String,
+    pub away_team: String,
+    /// Scheduled kickoff (unix timestamp)
+    pub kickoff_ts: i64,
+}
+
+impl MarketMetadata {
+    pub const MAX_TITLE_LEN: usize = 64;
+    pub const MAX_RESOLUTION_CRITERIA_LEN: usize = 256;
+    pub const MAX_CATEGORY_LEN: usize = 16;
+    pub const MAX_EVENT_ID_LEN: usize = 32;
+    pub const MAX_TEAM_LEN: usize = 4;
+
+    pub const SIZE: usize =
+          32   // market
+        + 4 + Self::MAX_TITLE_LEN                 // title
+        + 4 + Self::MAX_RESOLUTION_CRITERIA_LEN   // resolution_criteria
+        + 32   // resolution_criteria_hash
+        + 4 + Self::MAX_CATEGORY_LEN              // category
+        + 4 + Self::MAX_EVENT_ID_LEN              // event_id
+        + 4 + Self::MAX_TEAM_LEN                  // home_team
+        + 4 + Self::MAX_TEAM_LEN                  // away_team
+        + 8    // kickoff_ts
+    ;
+
+    /// Overwrite the description, checking every field fits the account.
+    pub fn set(&mut self, params: MarketMetadataParams) -> Result<()> {
+        require!(
+            params.title.len() <= Self::MAX_TITLE_LEN
+                && params.resolution_criteria.len() <= Self::MAX_RESOLUTION_CRITERIA_LEN
+                && params.category.len() <= Self::MAX_CATEGORY_LEN
+                && params.event_id.len() <= Self::MAX_EVENT_ID_LEN
+                && params.home_team.len() <= Self::MAX_TEAM_LEN
+                && params.away_team.len() <= Self::MAX_TEAM_LEN,
+            NflError::MetadataTooLong
+        );
+
+        self.title = params.title;
+        self.resolution_criteria = params.resolution_criteria;
+        self.resolution_criteria_hash = params.resolution_criteria_hash;
+        self.category = params.category;
+        self.event_id = params.event_id;
+        self.home_team = params.home_team;
+        self.away_team = params.away_team;
+        self.kickoff_ts = params.kickoff_ts;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarketKind {
+    /// YES / NO on a single proposition
+    Binary,
+    /// LONG (YES) / SHORT (NO) on a numeric result, paid linearly over [lower, upper]
+    Scalar { lower: i64, upper: i64 },
+}
+
+/// A market on N mutually exclusive outcomes, each with its own outcome mint.
+/// A complete set (one token of every outcome) is backed by one unit of collateral in `vault`.
+/// Once resolved, only the winning outcome's tokens are redeemable, or every outcome pays
+/// 1/N if the market is voided.
+#[account]
+pub struct CategoricalMarket {
+    pub authority: Pubkey,
+    /// Key allowed to resolve the market
+    pub resolver: Pubkey,
+    pub base_mint: Pubkey,
+    pub vault: Pubkey,
+    pub expiry_ts: i64,
+    /// Complete sets can be minted from this unix timestamp...
+    pub trading_open_ts: i64,
+    /// ...until this one
+    pub trading_close_ts: i64,
+    pub status: MarketStatus,
+    pub num_outcomes: u8,
+    /// Outcome mints in index order; complete once it holds `num_outcomes` keys
+    pub outcome_mints: Vec<Pubkey>,
+    /// Set at resolution
+    pub outcome: CategoricalOutcome,
+    pub market_authority_bump: u8,
+}
+
+impl CategoricalMarket {
+    /// `mint_sets` and `merge_sets` pass a mint and a token account per outcome, and a legacy
+    /// transaction (1232 bytes) fits 13 such pairs when the user also pays the fee.
+    pub const MAX_OUTCOMES: u8 = 13;
+
+    pub const SIZE: usize =
+          32   // authority
+        + 32   // resolver
+        + 32   // base_mint
+        + 32   // vault
+        + 8    // expiry_ts
+        + 8    // trading_open_ts
+        + 8    // trading_close_ts
+        + 1    // status
+        + 1    // num_outcomes
+        + 4 + 32 * Self::MAX_OUTCOMES as usize   // outcome_mints
+        + 1 + 1   // outcome
+        + 1    // market_authority_bump
+    ;
+
+    /// Check that the market is open and the clock is inside its trading window.
+    pub fn require_trading_open(&self) -> Result<()> {
+        require_trading_open(self.status, self.trading_open_ts, self.trading_close_ts)
+    }
+}
+
+/// Order book header (zero-copy).
+/// The account data continues with `capacity` order slots; the first `order_count`
+/// are live and kept sorted by outcome, side, best price and then time.
+#[account(zero_copy)]
+pub struct OrderBook {
+    pub market: Pubkey,
+    pub next_order_id: u64,
+    pub capacity: u64,
+    pub order_count: u64,
+    /// Fee charged to makers in basis points of the fill cost; negative for a rebate
+    pub maker_fee_bps: i16,
+    /// Fee charged to takers in basis points of the fill cost
+    pub taker_fee_bps: u16,
+    pub _padding: [u8; 4],
+    /// Limit prices must be a multiple of this
+    pub tick_size: u64,
+    /// Order quantities must be a multiple of this
+    pub lot_size: u64,
+    /// Smallest quantity any order may be placed with
+    pub min_order_size: u64,
+}
+
+impl OrderBook {
+    pub const SIZE: usize =
+          32   // market
+        + 8    // next_order_id
+        + 8    // capacity
+        + 8    // order_count
+        + 2    // maker_fee_bps
+        + 2    // taker_fee_bps
+        + 4    // padding
+        + 8    // tick_size
+        + 8    // lot_size
+        + 8    // min_order_size
+    ;
+
+    /// Set the price and size grid. `tick_size * lot_size` must be a multiple of PRICE_SCALE,
+    /// so an order on the grid always costs a whole number of collateral units.
+    pub fn set_grid(&mut self, tick_size: u64, lot_size: u64, min_order_size: u64) -> Result<()> {
+        require!(tick_size > 0 && lot_size > 0, NflError::InvalidOrderBookGrid);
+        let grid_cost = (tick_size as u128).checked_mul(lot_size as u128).ok_or(NflError::MathOverflow)?;
+        require!(grid_cost.checked_rem(PRICE_SCALE as u128) == Some(0), NflError::InvalidOrderBookGrid);
+
+        self.tick_size = tick_size;
+        self.lot_size = lot_size;
+        self.min_order_size = min_order_size;
+        Ok(())
+    }
+
+    /// Check an incoming order against the book's grid: `quantity` in whole lots and at least
+    /// the minimum order size, and the limit `price` (if any) on a tick within (0, PRICE_SCALE].
+    pub fn require_valid_order(&self, price: Option<u64>, quantity: u64) -> Result<()> {
+        if let Some(price) = price {
+            require!(price > 0 && price <= PRICE_SCALE, NflError::PriceOutOfRange);
+            require!(price.checked_rem(self.tick_size) == Some(0), NflError::InvalidTickPrice);
+        }
+        require!(quantity.checked_rem(self.lot_size) == Some(0), NflError::InvalidLotQuantity);
+        require!(quantity >= self.min_order_size, NflError::OrderTooSmall);
+        Ok(())
+    }
+
+    /// Account space for a book with `capacity` order slots.
+    pub const fn space(capacity: usize) -> usize {
+        8 + Self::SIZE + Order::SIZE * capacity
+    }
+
+    /// Borrow the header and order slots of a book for writing.
+    /// The borrow must be dropped before any CPI that includes the book.
+    pub fn load_book_mut<'a>(loader: &'a AccountLoader<'_, OrderBook>) -> Result<BookMut<'a>> {
+        let info = loader.as_ref();
+        require!(info.is_writable, ErrorCode::AccountNotMutable);
+        let data = info.try_borrow_mut_data()?;
+        let (header, slots) = RefMut::map_split(data, |data| {
+            let (header, slots) = data[8..].split_at_mut(Self::SIZE);
+            let len = slots.len() / Order::SIZE * Order::SIZE;
+            (
+                bytemuck::from_bytes_mut::<OrderBook>(header),
+                bytemuck::cast_slice_mut::<u8, Order>(&mut slots[..len]),
+            )
+        });
+        Ok(BookMut { header, slots })
+    }
+
+    /// Best resting order for one outcome and side that has not expired at `now`, if any.
+    pub fn best_order(loader: &AccountLoader<'_, OrderBook>, is_yes: bool, side: Side, now: i64) -> Result<Option<Order>> {
+        let orders = Self::load_orders(loader)?;
+        Ok(orders[book_segment(&orders, is_yes, side)].iter().find(|o| !o.is_expired(now)).copied())
+    }
+
+    /// Highest price a buyer of one outcome may pay at `now`: together with the best live ask
+    /// on the other outcome, a YES and a NO never cost more than the PRICE_SCALE they merge back for.
+    pub fn pair_price_cap(loader: &AccountLoader<'_, OrderBook>, is_yes: bool, now: i64) -> Result<u64> {
+        let other_ask = Self::best_order(loader, !is_yes, Side::Ask, now)?;
+        Ok(other_ask.map_or(PRICE_SCALE, |ask| PRICE_SCALE.saturating_sub(ask.price)))
+    }
+
+    /// Borrow the live orders of a book for reading.
+    pub fn load_orders<'a>(loader: &'a AccountLoader<'_, OrderBook>) -> Result<Ref<'a, [Order]>> {
+        let data = loader.as_ref().try_borrow_data()?;
+        Ok(Ref::map(data, |data| {
+            let header = bytemuck::from_bytes::<OrderBook>(&data[8..8 + Self::SIZE]);
+            let end = 8 + Self::SIZE + header.order_count as usize * Order::SIZE;
+            bytemuck::cast_slice::<u8, Order>(&data[8 + Self::SIZE..end])
+        }))
+    }
+
+    /// Decode the header and live orders from raw account data, e.g. when fetched off-chain.
+    pub fn unpack(data: &[u8]) -> Result<(OrderBook, Vec<Order>)> {
+        require!(
+            data.len() >= 8 + Self::SIZE && data[..8] == *Self::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        let header: OrderBook = bytemuck::pod_read_unaligned(&data[8..8 + Self::SIZE]);
+        let orders = data[8 + Self::SIZE..]
+            .chunks_exact(Order::SIZE)
+            .take(header.order_count as usize)
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+        Ok((header, orders))
+    }
+}
+
+/// Mutable view of an order book: its header and the sorted order slots behind it.
+pub struct BookMut<'a> {
+    pub header: RefMut<'a, OrderBook>,
+    slots: RefMut<'a, [Order]>,
+}
+
+impl BookMut<'_> {
+    /// Live orders, best first within each outcome and side.
+    pub fn orders(&self) -> &[Order] {
+        &self.slots[..self.header.order_count as usize]
+    }
+
+    /// Allocate the next order id.
+    pub fn take_order_id(&mut self) -> Result<u64> {
+        let order_id = self.header.next_order_id;
+        self.header.next_order_id = order_id.checked_add(1).ok_or(NflError::MathOverflow)?;
+        Ok(order_id)
+    }
+
+    /// Insert an order at its price-time position.
+    pub fn insert(&mut self, order: Order) -> Result<()> {
+        let count = self.header.order_count as usize;
+        if count as u64 >= self.header.capacity || count >= self.slots.len() {
+            return err!(NflError::OrderBookFull);
+        }
+        let key = order.book_key();
+        let index = self.orders().partition_point(|o| o.book_key() < key);
+        self.slots.copy_within(index..count, index + 1);
+        self.slots[index] = order;
+        self.header.order_count += 1;
+        Ok(())
+    }
+
+    /// Remove and return the order at `index`, keeping the rest in order.
+    pub fn remove(&mut self, index: usize) -> Order {
+        let count = self.header.order_count as usize;
+        let order = self.slots[index];
+        self.slots.copy_within(index + 1..count, index);
+        self.slots[count - 1] = Order::zeroed();
+        self.header.order_count -= 1;
+        order
+    }
+
+    /// Keep only the orders matching `keep`, preserving their order.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Order) -> bool) {
+        let count = self.header.order_count as usize;
+        let mut kept = 0;
+        for i in 0..count {
+            if keep(&self.slots[i]) {
+                self.slots[kept] = self.slots[i];
+                kept += 1;
+            }
+        }
+        self.slots[kept..count].fill(Order::zeroed());
+        self.header.order_count = kept as u64;
+    }
+
+    /// Bring the benefits carried by `mm`'s resting orders in line with its current status.
+    /// Orders placed with benefits keep them, at the current rates, only while the maker is still
+    /// compliant; otherwise they lose them and move behind other orders at their price.
+    pub fn sync_maker_benefits(&mut self, mm: &MarketMaker) {
+        let count = self.header.order_count as usize;
+        let mut revoked = false;
+        for order in self.slots[..count].iter_mut() {
+            if order.owner != mm.maker || !order.has_priority() {
+                continue;
+            }
+            if !mm.benefits_active {
+                order.priority = 0;
+                order.fee_tier = 0;
+                order.rebate_share_bps = 0;
+                revoked = true;
+            } else if order.side() == Side::Ask {
+                order.fee_tier = mm.fee_tier;
+                order.rebate_share_bps = mm.rebate_share_bps;
+            }
+        }
+        if revoked {
+            // Ids are unique, so an unstable sort still restores price-time order
+            self.slots[..count].sort_unstable_by_key(|o| o.book_key());
+        }
+    }
+
+    /// Fill up to `quantity` against the resting `maker_side` orders of one outcome,
+    /// best price first and oldest first within a price, updating the book in place.
+    /// Orders expired at `now` are skipped. Matching stops at the first order priced beyond
+    /// `limit`; when `all_or_nothing`, such an order fails with TooExpensive / TooCheap
+    /// instead, and a partial fill with InsufficientLiquidity.
+    /// Orders owned by `taker` are never filled; `self_trade` decides what happens to them.
+    #[allow(clippy::too_many_arguments)]
+    fn match_orders(
+        &mut self,
+        maker_side: Side,
+        is_yes: bool,
+        quantity: u64,
+        limit: Option<u64>,
+        all_or_nothing: bool,
+        taker: Pubkey,
+        self_trade: SelfTradePrevention,
+        now: i64,
+    ) -> Result<Matched> {
+        let mut fills = Vec::new();
+        let mut refund: u64 = 0;
+        let mut taker_cancelled = false;
+        let mut remaining = quantity;
+        let mut index = book_segment(self.orders(), is_yes, maker_side).start;
+        while remaining > 0 {
+            let Some(order) = self.orders().get(index).filter(|o| o.is_yes() == is_yes && o.side() == maker_side) else {
+                break;
+            };
+
+            // Expired orders stay in place until purged, but can no longer be filled
+            if order.is_expired(now) {
+                index += 1;
+                continue;
+            }
+
+            if let Some(limit) = limit {
+                let beyond_limit = match maker_side {
+                    Side::Ask => order.price > limit,
+                    Side::Bid => order.price < limit,
+                };
+                if beyond_limit && all_or_nothing {
+                    return match maker_side {
+                        Side::Ask => err!(NflError::TooExpensive),
+                        Side::Bid => err!(NflError::TooCheap),
+                    };
+                }
+                if beyond_limit {
+                    break;
+                }
+            }
+
+            if order.owner == taker {
+                // Quantity cancelled from the resting order
+                let cancelled = match self_trade {
+                    SelfTradePrevention::CancelOldest => order.quantity,
+                    SelfTradePrevention::DecrementBoth => {
+                        let overlap = order.quantity.min(remaining);
+                        remaining -= overlap;
+                        // A resting order left below the minimum size is cancelled outright
+                        if order.quantity - overlap < self.header.min_order_size { order.quantity } else { overlap }
+                    }
+                    SelfTradePrevention::CancelNewest if all_or_nothing => return err!(NflError::SelfTrade),
+                    SelfTradePrevention::CancelNewest => {
+                        taker_cancelled = true;
+                        break;
+                    }
+                };
+
+                // The resting order gives back the escrow of the cancelled quantity:
+                // outcome tokens for an ask, collateral for a bid
+                let escrow = order.escrow_for_fill(cancelled)?;
+                let released = match maker_side {
+                    Side::Ask => cancelled,
+                    Side::Bid => escrow,
+                };
+                refund = refund.checked_add(released).ok_or(NflError::MathOverflow)?;
+                msg!("Self-Trade Prevented: ID={}, Mode={:?}, Qty={}", { order.id }, self_trade, cancelled);
+
+                let order = &mut self.slots[index];
+                order.quantity -= cancelled;
+                order.escrow -= escrow;
+                if order.quantity == 0 {
+                    self.remove(index);
+                }
+                continue;
+            }
+
+            let fill_amount = order.quantity.min(remaining);
+            let escrow = order.escrow_for_fill(fill_amount)?;
+            let cost = order_cost(order.price, fill_amount)?;
+            let mut taker_fee = taker_fee_for(cost, self.header.taker_fee_bps)?;
+            let maker_fee = match maker_side {
+                Side::Ask => maker_fee_for(cost, order.maker_fee_bps(self.header.maker_fee_bps, self.header.taker_fee_bps))?,
+                // A bid escrowed its maker fee on top of the cost when it was placed
+                Side::Bid => escrow as i64 - cost as i64,
+            };
+            if maker_fee < 0 {
+                // A rebate is funded by the taker fee; keep the net fee non-negative even if
+                // rates changed or rounding differs since the bid was placed
+                taker_fee = taker_fee.max(maker_fee.unsigned_abs());
+            }
+            fills.push(Fill {
+                order_id: order.id,
+                maker: order.owner,
+                maker_receive_ata: order.maker_receive_ata,
+                price: order.price,
+                quantity: fill_amount,
+                escrow,
+                cost,
+                taker_fee,
+                maker_fee,
+            });
+
+            let order = &mut self.slots[index];
+            order.quantity -= fill_amount;
+            order.escrow -= escrow;
+            remaining -= fill_amount;
+
+            // If order is fully filled, remove it
+            if order.quantity == 0 {
+                self.remove(index);
+            }
+        }
+
+        if all_or_nothing && remaining > 0 {
+            return err!(NflError::InsufficientLiquidity);
+        }
+        Ok(Matched { fills, remaining, refund, taker_cancelled })
+    }
+}
+
+/// Index range of the live orders for one outcome and side (best first).
+pub fn book_segment(orders: &[Order], is_yes: bool, side: Side) -> std::ops::Range<usize> {
+    let group = (is_yes as u8, side as u8);
+    let start = orders.partition_point(|o| (o.is_yes, o.side) < group);
+    let end = start + orders[start..].partition_point(|o| (o.is_yes, o.side) == group);
+    start..end
+}
+
+#[zero_copy]
+#[repr(C, packed)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Order {
+    pub id: u64,
+    pub owner: Pubkey,
+    /// Where the maker is paid on a fill: collateral ATA for asks, outcome token ATA for bids
+    pub maker_receive_ata: Pubkey,
+    /// Price per unit as scaled integer: price = actual_price * PRICE_SCALE
+    /// Example: 1_000_000_000 = 1, 500_000_000 = 0.5
+    pub price: u64,
+    pub quantity: u64,
+    /// 1 for YES, 0 for NO; see `is_yes()`
+    is_yes: u8,
+    /// `Side` discriminant; see `side()`
+    side: u8,
+    /// 1 while placed by a designated market maker whose benefits are active; see `has_priority()`
+    priority: u8,
+    /// Designated market maker fee tier of an ask, kept in step with the maker's benefits;
+    /// see `maker_fee_bps()`
+    pub fee_tier: u8,
+    /// Designated market maker share of the taker fee for an ask, kept in step with the maker's
+    /// benefits; see `maker_fee_bps()`
+    pub rebate_share_bps: u16,
+    /// Collateral still held in escrow for a bid (always 0 for asks)
+    pub escrow: u64,
+    /// Unix timestamp from which a GTT order can no longer be filled; 0 for none
+    pub expiry_ts: i64,
+}
+
+impl Order {
+    pub const SIZE: usize =
+          8    // id
+        + 32   // owner
+        + 32   // maker_receive_ata
+        + 8    // price
+        + 8    // quantity
+        + 1    // is_yes
+        + 1    // side
+        + 1    // priority
+        + 1    // fee_tier
+        + 2    // rebate_share_bps
+        + 8    // escrow
+        + 8    // expiry_ts
+    ;
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u64,
+        owner: Pubkey,
+        maker_receive_ata: Pubkey,
+        price: u64,
+        quantity: u64,
+        is_yes: bool,
+        side: Side,
+        market_maker: Option<&MarketMaker>,
+        escrow: u64,
+        expiry_ts: i64,
+    ) -> Self {
+        // A bid's fee is fixed in its escrow, so only asks carry fee benefits
+        let benefits = market_maker.filter(|mm| mm.benefits_active);
+        let fee_benefits = benefits.filter(|_| side == Side::Ask);
+        Order {
+            id,
+            owner,
+            maker_receive_ata,
+            price,
+            quantity,
+            is_yes: is_yes as u8,
+            side: side as u8,
+            priority: benefits.is_some() as u8,
+            fee_tier: fee_benefits.map_or(0, |mm| mm.fee_tier),
+            rebate_share_bps: fee_benefits.map_or(0, |mm| mm.rebate_share_bps),
+            escrow,
+            expiry_ts,
+        }
+    }
+
+    /// Whether a GTT order has reached its expiry at `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry_ts != 0 && now >= self.expiry_ts
+    }
+
+    pub fn is_yes(&self) -> bool {
+        self.is_yes != 0
+    }
+
+    pub fn side(&self) -> Side {
+        if self.side == Side::Bid as u8 { Side::Bid } else { Side::Ask }
+    }
+
+    pub fn has_priority(&self) -> bool {
+        self.priority != 0
+    }
+
+    /// The book's maker fee rate as it applies to this order's owner.
+    pub fn maker_fee_bps(&self, maker_fee_bps: i16, taker_fee_bps: u16) -> i16 {
+        dmm_maker_fee_bps(maker_fee_bps, taker_fee_bps, self.fee_tier, self.rebate_share_bps)
+    }
+
+    /// Sort key within the book: outcome, side, then best price, designated market makers' orders
+    /// and oldest id first.
+    fn book_key(&self) -> (u8, u8, u64, bool, u64) {
+        let price_rank = match self.side() {
+            Side::Ask => self.price,
+            Side::Bid => u64::MAX - self.price,
+        };
+        (self.is_yes, self.side, price_rank, !self.has_priority(), self.id)
+    }
+
+    /// Event reporting that this order left the book unfilled, returning `refund` of its escrow.
+    pub fn cancelled(&self, market: Pubkey, reason: CancelReason, refund: u64) -> OrderCancelled {
+        OrderCancelled {
+            market,
+            order_id: self.id,
+            owner: self.owner,
+            side: self.side(),
+            is_yes: self.is_yes(),
+            price: self.price,
+            quantity: self.quantity,
+            refund,
+            reason,
+        }
+    }
+
+    /// Share of a bid's escrow released when `fill_amount` of it is filled.
+    /// The final fill releases whatever is left, so no rounding dust is stranded.
+    pub fn escrow_for_fill(&self, fill_amount: u64) -> Result<u64> {
+        if fill_amount >= self.quantity {
+            return Ok(self.escrow);
+        }
+        let share = (self.escrow as u128)
+            .checked_mul(fill_amount as u128)
+            .ok_or(NflError::MathOverflow)?
+            .checked_div(self.quantity as u128)
+            .ok_or(NflError::MathOverflow)?;
+        Ok(share as u64)
+    }
+}
+
+const _: () = assert!(std::mem::size_of::<Order>() == Order::SIZE);
+const _: () = assert!(std::mem::size_of::<OrderBook>() == OrderBook::SIZE);
+
+/// Obligations and benefits of the designated market maker program on one order book.
+#[account]
+pub struct DmmConfig {
+    pub order_book: Pubkey,
+    pub max_spread: u64,
+    pub min_size: u64,
+    pub min_uptime_bps: u16,
+    pub min_samples: u64,
+    pub sample_interval_secs: i64,
+    pub fee_tier: u8,
+    pub rebate_share_bps: u16,
+}
+
+impl DmmConfig {
+    pub const SIZE: usize =
+          32   // order_book
+        + 8    // max_spread
+        + 8    // min_size
+        + 2    // min_uptime_bps
+        + 8    // min_samples
+        + 8    // sample_interval_secs
+        + 1    // fee_tier
+        + 2    // rebate_share_bps
+    ;
+}
+
+/// A designated market maker's measured compliance and currently granted benefits.
+#[account]
+pub struct MarketMaker {
+    pub order_book: Pubkey,
+    pub maker: Pubkey,
+    pub registered_ts: i64,
+    pub samples_total: u64,
+    pub samples_compliant: u64,
+    /// End of the last sampled period
+    pub last_sample_ts: i64,
+    /// Book's next order id when the maker was last sampled; only older orders count next time
+    pub sampled_order_id: u64,
+    /// Compliant makers' new orders rest ahead of other orders at the same price
+    pub benefits_active: bool,
+    pub fee_tier: u8,
+    pub rebate_share_bps: u16,
+}
+
+impl MarketMaker {
+    pub const SIZE: usize =
+          32   // order_book
+        + 32   // maker
+        + 8    // registered_ts
+        + 8    // samples_total
+        + 8    // samples_compliant
+        + 8    // last_sample_ts
+        + 8    // sampled_order_id
+        + 1    // benefits_active
+        + 1    // fee_tier
+        + 2    // rebate_share_bps
+    ;
+
+    /// Share of samples that were compliant, in basis points.
+    pub fn uptime_bps(&self) -> u16 {
+        if self.samples_total == 0 {
+            return 0;
+        }
+        (self.samples_compliant as u128 * BPS_DENOMINATOR as u128 / self.samples_total as u128) as u16
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// Sell order: escrows outcome tokens
+    Ask,
+    /// Buy order: escrows collateral
+    Bid,
+}
+
+/// Why an order left the book without being filled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CancelReason {
+    /// Cancelled by its owner
+    Owner,
+    /// Purged after its GTT expiry
+    Expired,
+    /// Purged once the market was resolved
+    MarketResolved,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeResolution<'info> {
+    #[account(mut)]
+    pub resolver: Signer<'info>,
 
     #[account(
-        init,
-        payer = authority,
-        space = 8 + Market::SIZE
+        mut,
+        has_one = resolver,
+        has_one = base_mint
     )]
     pub market: Account<'info, Market>,
 
@@ -552,31 +4492,83 @@ pub struct CreateMarket<'info> {
 
     #[account(
         init,
-        payer = authority,
-        mint::decimals = base_mint.decimals,
-        mint::authority = market_authority
+        payer = resolver,
+        space = 8 + ResolutionProposal::SIZE,
+        seeds = [b"resolution_proposal", market.key().as_ref()],
+        bump
     )]
-    pub yes_mint: Account<'info, Mint>,
+    pub proposal: Account<'info, ResolutionProposal>,
 
     #[account(
         init,
-        payer = authority,
-        mint::decimals = base_mint.decimals,
-        mint::authority = market_authority
+        payer = resolver,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = market_authority
     )]
-    pub no_mint: Account<'info, Mint>,
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    /// Pays the bond
+    #[account(
+        mut,
+        constraint = resolver_collateral_ata.owner == resolver.key(),
+        constraint = resolver_collateral_ata.mint == base_mint.key(),
+    )]
+    pub resolver_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveMarket<'info> {
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = resolver,
+        has_one = base_mint
+    )]
+    pub market: Account<'info, Market>,
+
+    pub base_mint: Account<'info, Mint>,
 
+    /// Records the outcome like a finalized proposal, so the market closes the same way
     #[account(
         init,
-        payer = authority,
+        payer = resolver,
+        space = 8 + ResolutionProposal::SIZE,
+        seeds = [b"resolution_proposal", market.key().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    /// Stays empty; created so `close_market` finds the same accounts as after a proposal
+    #[account(
+        init,
+        payer = resolver,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump,
         token::mint = base_mint,
         token::authority = market_authority
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub bond_vault: Account<'info, TokenAccount>,
 
     #[account(
         seeds = [b"market_auth", market.key().as_ref()],
-        bump
+        bump = market.market_authority_bump
     )]
     /// CHECK: PDA authority, no data.
     pub market_authority: UncheckedAccount<'info>,
@@ -587,77 +4579,181 @@ pub struct CreateMarket<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct InitializeOrderBook<'info> {
+pub struct VoteResolution<'info> {
+    pub member: Signer<'info>,
+
     #[account(mut)]
-    pub authority: Signer<'info>,
-    
+    pub market: Account<'info, Market>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Dispute<'info> {
+    pub disputer: Signer<'info>,
+
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+
     #[account(
-        init, 
-        payer = authority, 
-        space = 8 + 32 + 8 + 8 + 4 + (89 * 100), 
-        seeds = [b"orderbook", market.key().as_ref()], 
+        mut,
+        seeds = [b"resolution_proposal", market.key().as_ref()],
         bump
     )]
-    pub order_book: Account<'info, OrderBook>,
-    
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    /// Pays the bond
+    #[account(
+        mut,
+        constraint = disputer_collateral_ata.owner == disputer.key(),
+        constraint = disputer_collateral_ata.mint == market.base_mint,
+    )]
+    pub disputer_collateral_ata: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeResolution<'info> {
+    #[account(mut)]
     pub market: Account<'info, Market>,
-    
-    pub yes_mint: Account<'info, Mint>,
-    pub no_mint: Account<'info, Mint>,
 
     #[account(
-        init,
-        payer = authority,
-        token::mint = yes_mint,
-        token::authority = order_book,
-        seeds = [b"yes_vault", order_book.key().as_ref()],
+        mut,
+        seeds = [b"resolution_proposal", market.key().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub escalation_resolver: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = escalation_resolver
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"resolution_proposal", market.key().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimBond<'info> {
+    pub winner: Signer<'info>,
+
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"resolution_proposal", market.key().as_ref()],
+        bump,
+        constraint = proposal.bond_winner == Some(winner.key()) @ NflError::NotBondWinner
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
         bump
     )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    /// Any collateral account the winner picks
+    #[account(
+        mut,
+        constraint = winner_collateral_ata.mint == market.base_mint @ NflError::InvalidBaseMint,
+    )]
+    pub winner_collateral_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"market_auth", market.key().as_ref()],
+        bump = market.market_authority_bump
+    )]
+    /// CHECK: PDA authority, no data.
+    pub market_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub market: Account<'info, Market>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOrderBook<'info> {
+    /// Receives the rent of the book and its vaults
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority)]
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump, close = authority)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(mut, seeds = [b"yes_vault", order_book.key().as_ref()], bump)]
     pub yes_vault: Account<'info, TokenAccount>,
 
+    #[account(mut, seeds = [b"no_vault", order_book.key().as_ref()], bump)]
+    pub no_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"collateral_vault", order_book.key().as_ref()], bump)]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"fee_vault", order_book.key().as_ref()], bump)]
+    pub fee_vault: Account<'info, TokenAccount>,
+
     #[account(
-        init,
-        payer = authority,
-        token::mint = no_mint,
-        token::authority = order_book,
-        seeds = [b"no_vault", order_book.key().as_ref()],
-        bump
+        mut,
+        constraint = treasury_collateral_ata.owner == market.treasury @ NflError::InvalidTreasury,
+        constraint = treasury_collateral_ata.mint == market.base_mint @ NflError::InvalidBaseMint,
     )]
-    pub no_vault: Account<'info, TokenAccount>,
+    pub treasury_collateral_ata: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct MintPairs<'info> {
+pub struct CloseMarket<'info> {
+    /// Receives the rent of the market and its accounts
     #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        constraint = user_collateral_ata.owner == user.key(),
-        constraint = user_collateral_ata.mint == market.base_mint
-    )]
-    pub user_collateral_ata: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        has_one = base_mint,
+        has_one = authority,
         has_one = yes_mint,
         has_one = no_mint,
-        has_one = vault
+        has_one = vault,
+        close = authority
     )]
     pub market: Account<'info, Market>,
 
-    pub base_mint: Account<'info, Mint>,
-
-    #[account(mut)]
     pub yes_mint: Account<'info, Mint>,
-
-    #[account(mut)]
     pub no_mint: Account<'info, Mint>,
 
     #[account(mut)]
@@ -665,17 +4761,35 @@ pub struct MintPairs<'info> {
 
     #[account(
         mut,
-        constraint = user_yes_ata.owner == user.key(),
-        constraint = user_yes_ata.mint == yes_mint.key()
+        seeds = [b"market_metadata", market.key().as_ref()],
+        bump,
+        close = authority
     )]
-    pub user_yes_ata: Account<'info, TokenAccount>,
+    pub metadata: Account<'info, MarketMetadata>,
 
+    /// CHECK: Only checked to be closed (or never created).
+    #[account(seeds = [b"orderbook", market.key().as_ref()], bump)]
+    pub order_book: UncheckedAccount<'info>,
+
+    /// Required if the market was resolved through a proposal, i.e. has no committee
     #[account(
         mut,
-        constraint = user_no_ata.owner == user.key(),
-        constraint = user_no_ata.mint == no_mint.key()
+        seeds = [b"resolution_proposal", market.key().as_ref()],
+        bump,
+        close = authority
     )]
-    pub user_no_ata: Account<'info, TokenAccount>,
+    pub proposal: Option<Account<'info, ResolutionProposal>>,
+
+    /// Required if the market was resolved through a proposal, i.e. has no committee
+    #[account(mut, seeds = [b"bond_vault", market.key().as_ref()], bump)]
+    pub bond_vault: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = treasury_collateral_ata.owner == market.treasury @ NflError::InvalidTreasury,
+        constraint = treasury_collateral_ata.mint == market.base_mint @ NflError::InvalidBaseMint,
+    )]
+    pub treasury_collateral_ata: Account<'info, TokenAccount>,
 
     #[account(
         seeds = [b"market_auth", market.key().as_ref()],
@@ -688,119 +4802,19 @@ pub struct MintPairs<'info> {
 }
 
 #[derive(Accounts)]
-pub struct PlaceLimitSell<'info> {
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    
-    #[account(mut)]
-    pub seller_token_ata: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub seller_receive_collateral_ata: Account<'info, TokenAccount>,
-    
-    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
-    pub order_book: Account<'info, OrderBook>,
-    
-    #[account(
-        mut, 
-        seeds = [b"yes_vault", order_book.key().as_ref()],
-        bump
-    )]
-    pub yes_vault: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut, 
-        seeds = [b"no_vault", order_book.key().as_ref()],
-        bump
-    )]
-    pub no_vault: Account<'info, TokenAccount>,
-    
-    pub market: Account<'info, Market>,
-    pub token_program: Program<'info, Token>,
-}
+pub struct SetResolver<'info> {
+    pub authority: Signer<'info>,
 
-#[derive(Accounts)]
-pub struct MarketBuyAccounts<'info> {
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    
-    #[account(mut, constraint = buyer_collateral_ata.mint == market.base_mint)]
-    pub buyer_collateral_ata: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub buyer_receive_token_ata: Account<'info, TokenAccount>,
-    
-    pub market: Account<'info, Market>,
-    
-    #[account(mut, seeds = [b"orderbook", market.key().as_ref()], bump)]
-    pub order_book: Account<'info, OrderBook>,
-    
     #[account(
-        mut, 
-        seeds = [b"yes_vault", order_book.key().as_ref()],
-        bump
-    )]
-    pub yes_vault: Account<'info, TokenAccount>,
-    #[account(
-        mut, 
-        seeds = [b"no_vault", order_book.key().as_ref()],
-        bump
+        mut,
+        has_one = authority
     )]
-    pub no_vault: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-}
-
-#[account]
-pub struct Market {
-    pub authority: Pubkey,
-    pub base_mint: Pubkey,
-    pub yes_mint: Pubkey,
-    pub no_mint: Pubkey,
-    pub vault: Pubkey,
-    pub expiry_ts: i64,
-    pub status: MarketStatus,
-    pub outcome: Outcome,
-    pub market_authority_bump: u8,
-}
-
-impl Market {
-    pub const SIZE: usize = 
-          32   // authority
-        + 32   // base_mint
-        + 32   // yes_mint
-        + 32   // no_mint
-        + 32   // vault
-        + 8    // expiry_ts
-        + 1    // status
-        + 1    // outcome
-        + 1    // market_authority_bump
-    ;
-}
-
-#[account]
-pub struct OrderBook {
-    pub market: Pubkey,
-    pub next_order_id: u64,
-    pub capacity: u64,
-    pub orders: Vec<Order>,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
-pub struct Order {
-    pub id: u64,
-    pub owner: Pubkey,
-    pub seller_receive_collateral_ata: Pubkey,
-    /// Price per unit as scaled integer: price = actual_price * PRICE_SCALE
-    /// Example: 1_500_000_000 = 1.5, 500_000_000 = 0.5
-    pub price: u64,
-    pub quantity: u64,
-    pub is_yes: bool,
+    pub market: Account<'info, Market>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ResolveMarket<'info> {
-    #[account(mut)]
+pub struct UpdateMarketStatus<'info> {
     pub authority: Signer<'info>,
 
     #[account(
@@ -810,6 +4824,7 @@ pub struct ResolveMarket<'info> {
     pub market: Account<'info, Market>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Redeem<'info> {
     #[account(mut)]
@@ -864,12 +4879,23 @@ pub struct Redeem<'info> {
     pub market_authority: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
+
+    /// Optional settlement account for the payout; defaults to `user_collateral_ata`
+    #[account(
+        mut,
+        constraint = recipient_collateral_ata.mint == base_mint.key(),
+    )]
+    pub recipient_collateral_ata: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MarketStatus { 
     Open, 
     Halted, 
+    /// An outcome has been proposed and is in its challenge period
+    Proposed,
+    /// The proposal was disputed and awaits the escalation resolver
+    Disputed,
     Resolved 
 }
 
@@ -878,7 +4904,283 @@ pub enum Outcome {
     Pending, 
     Yes, 
     No, 
-    Invalid 
+    Invalid,
+    /// Game ended level; both sides are paid half
+    Tie,
+    /// Final value of a scalar market (e.g. total points)
+    Scalar(i64),
+}
+
+/// Final outcome of a categorical market.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CategoricalOutcome {
+    Pending,
+    /// Index of the winning outcome
+    Winner(u8),
+    /// Voided market; every outcome pays 1/N
+    Invalid,
+}
+
+impl Outcome {
+    /// Payout split (yes_bps, no_bps) implied by a final outcome, or None while pending.
+    /// A tie or a void (Invalid) market refunds both sides equally.
+    pub fn payout_split(&self) -> Option<(u16, u16)> {
+        let half = BPS_DENOMINATOR / 2;
+        match self {
+            Outcome::Pending | Outcome::Scalar(_) => None,
+            Outcome::Yes => Some((BPS_DENOMINATOR, 0)),
+            Outcome::No => Some((0, BPS_DENOMINATOR)),
+            Outcome::Tie | Outcome::Invalid => Some((half, half)),
+        }
+    }
+}
+
+/// Check that a market is open and the clock is inside its trading window [open_ts, close_ts).
+fn require_trading_open(status: MarketStatus, open_ts: i64, close_ts: i64) -> Result<()> {
+    require!(status == MarketStatus::Open, NflError::MarketNotOpen);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= open_ts, NflError::TradingNotStarted);
+    require!(now < close_ts, NflError::TradingClosed);
+    Ok(())
+}
+
+/// Payout split (long_bps, short_bps) of a scalar market resolving to `value`.
+/// LONG earns the share of [lower, upper] below the value, clamped at the bounds;
+/// SHORT earns the rest, so the two always sum to BPS_DENOMINATOR.
+pub fn scalar_payout_split(lower: i64, upper: i64, value: i64) -> (u16, u16) {
+    let clamped = value.clamp(lower, upper);
+    let long_bps = ((clamped as i128 - lower as i128) * BPS_DENOMINATOR as i128
+        / (upper as i128 - lower as i128)) as u16;
+    (long_bps, BPS_DENOMINATOR - long_bps)
+}
+
+/// Collateral owed for `amount` outcome tokens paying `payout_bps`, rounded down.
+pub fn payout_for(amount: u64, payout_bps: u16) -> Result<u64> {
+    let payout = (amount as u128)
+        .checked_mul(payout_bps as u128)
+        .ok_or(NflError::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(NflError::MathOverflow)?;
+    Ok(payout as u64)
+}
+
+/// Collateral owed for a YES and NO balance together, rounded down in a single step
+/// so a complete pair always redeems for its full collateral on a split outcome.
+pub fn pair_payout_for(yes_amount: u64, yes_bps: u16, no_amount: u64, no_bps: u16) -> Result<u64> {
+    let weighted = (yes_amount as u128)
+        .checked_mul(yes_bps as u128)
+        .and_then(|yes| yes.checked_add((no_amount as u128).checked_mul(no_bps as u128)?))
+        .ok_or(NflError::MathOverflow)?;
+    let payout = weighted / BPS_DENOMINATOR as u128;
+    u64::try_from(payout).map_err(|_| error!(NflError::MathOverflow))
+}
+
+// --- Events ---
+// Emitted through a self-CPI (`emit_cpi!`), so indexers read them from the inner
+// instructions of a transaction instead of its logs, which can be truncated.
+
+#[event]
+pub struct MarketCreated {
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub event_id: String,
+    pub kind: MarketKind,
+    pub resolver: Pubkey,
+    pub base_mint: Pubkey,
+    pub yes_mint: Pubkey,
+    pub no_mint: Pubkey,
+    pub vault: Pubkey,
+    pub expiry_ts: i64,
+    pub trading_open_ts: i64,
+    pub trading_close_ts: i64,
+}
+
+#[event]
+pub struct PairsMinted {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PairsMerged {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+/// A halt or resume of a binary, scalar or categorical market.
+#[event]
+pub struct MarketStatusChanged {
+    pub market: Pubkey,
+    pub status: MarketStatus,
+}
+
+#[event]
+pub struct OrderPlaced {
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: Side,
+    pub is_yes: bool,
+    pub price: u64,
+    pub quantity: u64,
+    /// Outcome tokens escrowed by an ask, collateral (including the maker fee) by a bid
+    pub escrow: u64,
+    /// Expiry of a GTT order; 0 for none
+    pub expiry_ts: i64,
+}
+
+/// One resting order filled, fully or partly, by a taker.
+#[event]
+pub struct OrderFilled {
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Side of the resting order: `Ask` when the taker bought, `Bid` when they sold
+    pub maker_side: Side,
+    pub is_yes: bool,
+    pub price: u64,
+    pub quantity: u64,
+    /// Notional value of the fill: (price * quantity) / PRICE_SCALE
+    pub cost: u64,
+    pub taker_fee: u64,
+    /// Negative for a rebate
+    pub maker_fee: i64,
+}
+
+/// Emitted by whichever path settles the market: `finalize_resolution`,
+/// `resolve_dispute` or the deciding `vote_resolution`.
+/// An order taken off the book unfilled, by its owner or by a purge crank.
+#[event]
+pub struct OrderCancelled {
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: Side,
+    pub is_yes: bool,
+    pub price: u64,
+    /// Unfilled quantity removed from the book
+    pub quantity: u64,
+    /// Escrow returned to the owner: outcome tokens for an ask, collateral for a bid
+    pub refund: u64,
+    pub reason: CancelReason,
+}
+
+#[event]
+pub struct FeesSet {
+    pub market: Pubkey,
+    pub maker_fee_bps: i16,
+    pub taker_fee_bps: u16,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub market: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ResolutionProposed {
+    pub market: Pubkey,
+    pub proposer: Pubkey,
+    pub outcome: Outcome,
+    pub bond: u64,
+    pub challenge_ends_ts: i64,
+}
+
+#[event]
+pub struct ResolutionDisputed {
+    pub market: Pubkey,
+    pub disputer: Pubkey,
+    pub proposed_outcome: Outcome,
+    /// Bond posted by the disputer, matching the proposer's
+    pub bond: u64,
+}
+
+#[event]
+pub struct BondClaimed {
+    pub market: Pubkey,
+    pub winner: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CommitteeVoted {
+    pub market: Pubkey,
+    pub member: Pubkey,
+    pub outcome: Outcome,
+    /// Members whose current vote is `outcome`, including this one
+    pub agreeing: u8,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct MarketResolved {
+    pub market: Pubkey,
+    pub outcome: Outcome,
+    pub payout_yes_bps: u16,
+    pub payout_no_bps: u16,
+    pub resolved_ts: i64,
+}
+
+#[event]
+pub struct Redeemed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub recipient: Pubkey,
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub payout: u64,
+}
+
+#[event]
+pub struct CategoricalMarketCreated {
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub resolver: Pubkey,
+    pub base_mint: Pubkey,
+    pub vault: Pubkey,
+    pub num_outcomes: u8,
+    pub expiry_ts: i64,
+    pub trading_open_ts: i64,
+    pub trading_close_ts: i64,
+}
+
+#[event]
+pub struct SetsMinted {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SetsMerged {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CategoricalMarketResolved {
+    pub market: Pubkey,
+    pub outcome: CategoricalOutcome,
+    pub resolved_ts: i64,
+}
+
+/// A winning redemption, or a refund of a voided market.
+#[event]
+pub struct CategoricalRedeemed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub recipient: Pubkey,
+    pub outcome: CategoricalOutcome,
+    /// Outcome tokens burned, over every outcome for a refund
+    pub burned: u64,
+    pub payout: u64,
 }
 
 #[error_code]
@@ -887,7 +5189,7 @@ pub enum NflError {
     InvalidExpiry,
     #[msg("Mint amount must be positive.")]
     InvalidAmount,
-    #[msg("Market is not open for minting.")]
+    #[msg("Market is not open for minting or trading.")]
     MarketNotOpen,
     #[msg("Base mint does not match market config.")]
     InvalidBaseMint,
@@ -907,7 +5209,7 @@ pub enum NflError {
     InvalidResolutionOutcome,
     #[msg("Cannot redeem in current market outcome.")]
     CannotRedeemForOutcome,
-    #[msg("User has no winning tokens to redeem.")]
+    #[msg("User has no paying tokens to redeem.")]
     NothingToRedeem,
     // Order Book Errors
     #[msg("Order book is full")] 
@@ -922,4 +5224,126 @@ pub enum NflError {
     TooExpensive,
     #[msg("Insufficient liquidity to fill order")] 
     InsufficientLiquidity,
+    #[msg("Order not found")]
+    OrderNotFound,
+    #[msg("Signer does not own this order")]
+    NotOrderOwner,
+    #[msg("Token account does not match the order's outcome mint")]
+    InvalidOutcomeTokenAccount,
+    #[msg("Order would cross the opposite side of the book")]
+    OrderWouldCross,
+    #[msg("Too cheap")]
+    TooCheap,
+    // Market Maker Errors
+    #[msg("Invalid market maker program configuration")]
+    InvalidDmmConfig,
+    #[msg("Market maker was sampled too recently")]
+    SampleTooSoon,
+    // Market Status Errors
+    #[msg("Market is not halted.")]
+    MarketNotHalted,
+    // Order Book Capacity Errors
+    #[msg("Order book capacity must be positive")]
+    InvalidOrderBookCapacity,
+    // Order Size Errors
+    #[msg("Tick size and lot size must be positive, with tick size times lot size a multiple of PRICE_SCALE")]
+    InvalidOrderBookGrid,
+    #[msg("Price must be above 0 and at most PRICE_SCALE (1 unit of collateral)")]
+    PriceOutOfRange,
+    #[msg("Price must be a multiple of the tick size")]
+    InvalidTickPrice,
+    #[msg("Quantity must be a multiple of the lot size")]
+    InvalidLotQuantity,
+    #[msg("Quantity is below the minimum order size")]
+    OrderTooSmall,
+    // Fee Errors
+    #[msg("Invalid fee configuration")]
+    InvalidFeeConfig,
+    // Categorical Market Errors
+    #[msg("Categorical markets need between 2 and 13 outcomes")]
+    InvalidOutcomeCount,
+    #[msg("Outcome index is out of range or not the next outcome to add")]
+    InvalidOutcomeIndex,
+    #[msg("Not every outcome has a mint yet")]
+    OutcomeMintsIncomplete,
+    #[msg("Expected a mint and token account for every outcome in remaining_accounts")]
+    MissingOutcomeAccounts,
+    #[msg("Outcome mint does not match market config")]
+    InvalidOutcomeMint,
+    // Scalar Market Errors
+    #[msg("Scalar market lower bound must be below its upper bound")]
+    InvalidScalarRange,
+    // Market Metadata Errors
+    #[msg("Market metadata field is too long")]
+    MetadataTooLong,
+    #[msg("Market metadata can only be updated before trading starts")]
+    MetadataLocked,
+    // Market Address Errors
+    #[msg("Event id is empty or does not match the market address")]
+    InvalidEventId,
+    // Resolver Errors
+    #[msg("Resolver can only be changed before expiry")]
+    ResolverLocked,
+    // Optimistic Resolution Errors
+    #[msg("Challenge period must not be negative")]
+    InvalidResolutionConfig,
+    #[msg("Market has no undisputed resolution proposal")]
+    NoPendingProposal,
+    #[msg("Challenge period is over")]
+    ChallengePeriodOver,
+    #[msg("Challenge period has not ended yet")]
+    ChallengePeriodActive,
+    #[msg("Markets with a challenge period are resolved by proposal")]
+    ChallengePeriodRequired,
+    #[msg("Market resolution is not disputed")]
+    NotDisputed,
+    #[msg("Only the winner of a settled proposal can claim its bonds")]
+    NotBondWinner,
+    #[msg("There is no bond left to claim")]
+    NoBondToClaim,
+    #[msg("Bonds must be claimed before the market can be closed")]
+    BondNotClaimed,
+    #[msg("The resolution proposal and bond vault must be closed with the market")]
+    ProposalAccountsMissing,
+    // Resolution Committee Errors
+    #[msg("Committee must have distinct members and a majority threshold")]
+    InvalidCommittee,
+    #[msg("Committee markets are resolved by vote")]
+    CommitteeMarket,
+    #[msg("Signer is not on the market's resolution committee")]
+    NotCommitteeMember,
+    #[msg("Committee member has already cast this vote")]
+    AlreadyVoted,
+    // Closing Errors
+    #[msg("Order book still has resting orders or escrowed tokens")]
+    OrderBookNotEmpty,
+    #[msg("Order book must be closed before the market")]
+    OrderBookStillOpen,
+    #[msg("Market still has unredeemed tokens and its grace period has not ended")]
+    MarketNotRedeemed,
+    #[msg("Treasury account does not belong to the market's treasury")]
+    InvalidTreasury,
+    // Trading Window Errors
+    #[msg("Trading must open before it closes, and close no later than expiry")]
+    InvalidTradingWindow,
+    #[msg("Trading has not opened yet")]
+    TradingNotStarted,
+    #[msg("Trading has closed")]
+    TradingClosed,
+
+    // Time In Force Errors
+    #[msg("Order expiry must be in the future")]
+    InvalidOrderExpiry,
+    #[msg("Order has not expired")]
+    OrderNotExpired,
+
+    // Self-Trade Errors
+    #[msg("Post-only orders must rest on the book")]
+    PostOnlyMustRest,
+    #[msg("Order would trade against the taker's own order")]
+    SelfTrade,
+
+    // Pair Pricing Errors
+    #[msg("Price plus the best ask on the other outcome exceeds PRICE_SCALE")]
+    PairPriceAboveScale,
 }
\ No newline at end of file
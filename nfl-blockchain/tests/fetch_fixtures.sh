@@ -0,0 +1,7 @@
+#!/bin/bash
+# Dump the Metaplex Token Metadata program from mainnet for the local test validator
+# (see [[test.genesis]] in Anchor.toml).
+set -e
+
+mkdir -p tests/fixtures
+solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s tests/fixtures/mpl_token_metadata.so
@@ -8,6 +8,8 @@
 //!
 //! Usage:
 //!   export ANCHOR_WALLET=~/.config/solana/marketauth.json  # Market Authority's wallet
+//!   export EVENT_ID=<event_id>  # Optional, defaults to the sample game
+//!   export TRADING_SECS=<seconds>  # Optional, how long the market trades before it expires (default 300)
 //!   cargo run --bin demo1
 
 #![allow(deprecated)]
@@ -17,19 +19,6 @@ use anchor_client::solana_sdk::signature::Signer;
 use std::str::FromStr;
 use tests::test_utils::*;
 
-fn get_orderbook_pda(market: Pubkey) -> Pubkey {
-    Pubkey::find_program_address(
-        &[b"orderbook", market.as_ref()],
-        &nfl_blockchain::id(),
-    )
-    .0
-}
-
-fn get_ob_vault_pda(order_book: Pubkey, is_yes: bool) -> Pubkey {
-    let seed: &[u8] = if is_yes { b"yes_vault" } else { b"no_vault" };
-    Pubkey::find_program_address(&[seed, order_book.as_ref()], &nfl_blockchain::id()).0
-}
-
 fn main() {
     println!("Market Authority");
     println!("Creating a prediction market...");
@@ -50,46 +39,56 @@ fn main() {
 
     // Step 2: Create a prediction market
     println!("\nStep 2: Creating prediction market...");
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) =
-        create_market(&program, payer, base_mint);
+    let event_id = std::env::var("EVENT_ID").unwrap_or_else(|_| sample_metadata().event_id);
+    // Trading closes at expiry, after which demo4 can resolve the market
+    let trading_secs: i64 = std::env::var("TRADING_SECS").map_or(300, |secs| secs.parse().unwrap());
+    let expiry_ts = validator_now(&program) + trading_secs;
+    let (market, yes_mint, no_mint, _vault, _market_authority) =
+        create_market_for_event(&program, payer, base_mint, &event_id, expiry_ts).unwrap_or_else(|err| {
+            eprintln!("Error: could not create a market for event {}: {}", event_id, err);
+            eprintln!("A market authority can only create one market per event.");
+            std::process::exit(1);
+        });
+    println!("   [OK] Market created: {}", market);
+    println!("   [OK] Trading closes and the market expires at unix_ts={}", expiry_ts);
 
 
     // Step 3: Initialize order book
     println!("\nStep 3: Initializing order book...");
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
-    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
-    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let addresses = MarketAddresses::derive(payer.pubkey(), &event_id);
+    let order_book_pda = addresses.order_book;
+    let yes_vault_pda = addresses.yes_vault;
+    let no_vault_pda = addresses.no_vault;
+    let collateral_vault_pda = addresses.collateral_vault;
+    let fee_vault_pda = addresses.fee_vault;
 
+    // The demo builds with PRICE_SCALE=1000000000: prices on a 0.01 grid, quantities in lots of 100
     program
         .request()
         .accounts(nfl_blockchain::accounts::InitializeOrderBook {
             authority: payer.pubkey(),
             order_book: order_book_pda,
-            market: market_kp.pubkey(),
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
+            collateral_vault: collateral_vault_pda,
+            fee_vault: fee_vault_pda,
             token_program: anchor_spl::token::spl_token::id(),
             system_program: anchor_client::solana_sdk::system_program::id(),
             rent: anchor_client::solana_sdk::sysvar::rent::id(),
         })
-        .args(nfl_blockchain::instruction::InitializeOrderBook {})
+        .args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10_000_000, lot_size: 100, min_order_size: 100 })
         .send()
         .unwrap();
     println!("   [OK] Order book initialized: {}", order_book_pda);
 
     // Print market information for use in subsequent demos
 
-    println!("\nTo market info in next demos, export these as environment variables:");
-    println!("export MARKET={}", market_kp.pubkey());
-    println!("export BASE_MINT={}", base_mint);
-    println!("export YES_MINT={}", yes_mint_kp.pubkey());
-    println!("export NO_MINT={}", no_mint_kp.pubkey());
-    println!("export VAULT={}", vault_kp.pubkey());
-    println!("export MARKET_AUTHORITY={}", market_authority);
-    println!("export ORDER_BOOK={}", order_book_pda);
-    println!("export YES_VAULT={}", yes_vault_pda);
-    println!("export NO_VAULT={}", no_vault_pda);
+    println!("\nEvery market address is derived from the authority and event id.");
+    println!("To use this market in the next demos, export these as environment variables:");
+    println!("export AUTHORITY={}", payer.pubkey());
+    println!("export EVENT_ID={}", event_id);
 }
-
@@ -6,20 +6,12 @@
 //!
 //! Usage:
 //!   export ANCHOR_WALLET=~/.config/solana/user1.json  # User 1's wallet
-//!   export MARKET=<market_address>  # From demo1 output
-//!   export BASE_MINT=<base_mint_address>  # From demo1 output
-//!   export YES_MINT=<yes_mint_address>  # From demo1 output
-//!   export NO_MINT=<no_mint_address>  # From demo1 output
-//!   export VAULT=<vault_address>  # From demo1 output
-//!   export MARKET_AUTHORITY=<market_authority_address>  # From demo1 output
-//!   export ORDER_BOOK=<order_book_address>  # From demo1 output
-//!   export YES_VAULT=<yes_vault_address>  # From demo1 output
-//!   export NO_VAULT=<no_vault_address>  # From demo1 output
+//!   export AUTHORITY=<market_authority_wallet>  # From demo1 output
+//!   export EVENT_ID=<event_id>  # From demo1 output
 //!   cargo run --bin demo2
 
 #![allow(deprecated)]
 
-use anchor_client::solana_sdk::pubkey::Pubkey;
 use anchor_client::solana_sdk::signature::Signer;
 
 use tests::test_utils::*;
@@ -30,18 +22,11 @@ fn main() {
     println!("-------------\n");
 
     // Check for required environment variables
-    let market = get_env_pubkey("MARKET");
-    let base_mint = get_env_pubkey("BASE_MINT");
-    let yes_mint = get_env_pubkey("YES_MINT");
-    let no_mint = get_env_pubkey("NO_MINT");
-    let vault = get_env_pubkey("VAULT");
-    let market_authority = get_env_pubkey("MARKET_AUTHORITY");
-    let order_book = get_env_pubkey("ORDER_BOOK");
-    let yes_vault = get_env_pubkey("YES_VAULT");
-    let no_vault = get_env_pubkey("NO_VAULT");
+    let MarketAddresses { market, yes_mint, no_mint, vault, market_authority, order_book, yes_vault, no_vault, .. } = market_addresses_from_env();
 
     // Setup client as User 1
     let (program, payer) = setup_client();
+    let base_mint = program.account::<nfl_blockchain::Market>(market).unwrap().base_mint;
     println!("Setpu client as User 1, User 1 address: {}", payer.pubkey());
 
     // Step 2: Create collateral account and fund it
@@ -85,22 +70,30 @@ fn main() {
             no_vault,
             market,
             token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book),
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::PlaceLimitSell {
             price: sell_price,
             quantity: sell_quantity,
             is_yes: false, // Selling NO tokens
+            time_in_force: nfl_blockchain::TimeInForce::Gtc,
+            post_only: None,
+            self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
         })
         .send()
         .unwrap();
     println!("   [OK] Placed sell order: {} NO tokens at price {}", sell_quantity, sell_price);
 
     // Verify order was placed
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book).unwrap();
-    println!("\n   Order book now has {} order(s)", ob_account.orders.len());
-    if let Some(order) = ob_account.orders.first() {
+    let (_, orders) = fetch_order_book(&program, order_book);
+    println!("\n   Order book now has {} order(s)", orders.len());
+    if let Some(order) = orders.first() {
         println!("   Order details: ID={}, Price={}, Quantity={}, IsYes={}", 
-                 order.id, order.price, order.quantity, order.is_yes);
+                 { order.id }, { order.price }, { order.quantity }, order.is_yes());
     }
 
     // Check final balances
@@ -119,17 +112,3 @@ fn main() {
     println!("If another user wants to execute a buy, they must use User1's collateral ID:");
     println!("export USER1_COLLATERAL={}", user_collateral);
 }
-
-fn get_env_pubkey(name: &str) -> Pubkey {
-    std::env::var(name)
-        .unwrap_or_else(|_| {
-            eprintln!("Error: {} environment variable is not set.", name);
-            eprintln!("Please run demo1 first and export the market information.");
-            std::process::exit(1);
-        })
-        .parse()
-        .unwrap_or_else(|_| {
-            eprintln!("Error: Invalid {} address.", name);
-            std::process::exit(1);
-        })
-}
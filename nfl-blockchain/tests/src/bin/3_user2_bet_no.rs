@@ -6,15 +6,8 @@
 //!
 //! Usage:
 //!   export ANCHOR_WALLET=~/.config/solana/user2.json  # User 2's wallet
-//!   export MARKET=<market_address>  # From demo1 output
-//!   export BASE_MINT=<base_mint_address>  # From demo1 output
-//!   export YES_MINT=<yes_mint_address>  # From demo1 output
-//!   export NO_MINT=<no_mint_address>  # From demo1 output
-//!   export VAULT=<vault_address>  # From demo1 output
-//!   export MARKET_AUTHORITY=<market_authority_address>  # From demo1 output
-//!   export ORDER_BOOK=<order_book_address>  # From demo1 output
-//!   export YES_VAULT=<yes_vault_address>  # From demo1 output
-//!   export NO_VAULT=<no_vault_address>  # From demo1 output
+//!   export AUTHORITY=<market_authority_wallet>  # From demo1 output
+//!   export EVENT_ID=<event_id>  # From demo1 output
 //!   export USER1_COLLATERAL=<user1_collateral_ata>  # User 1's collateral account (to pay seller)
 //!   cargo run --bin demo3
 
@@ -33,26 +26,21 @@ fn main() {
 
 
     // Check for required environment variables
-    let market = get_env_pubkey("MARKET");
-    let base_mint = get_env_pubkey("BASE_MINT");
-    let _yes_mint = get_env_pubkey("YES_MINT");
-    let no_mint = get_env_pubkey("NO_MINT");
-    let order_book = get_env_pubkey("ORDER_BOOK");
-    let yes_vault = get_env_pubkey("YES_VAULT");
-    let no_vault = get_env_pubkey("NO_VAULT");
+    let MarketAddresses { market, no_mint, order_book, yes_vault, no_vault, .. } = market_addresses_from_env();
     let user1_collateral = get_env_pubkey("USER1_COLLATERAL");
 
     // Setup client as User 2
     println!("Step 1: Setting up client as User 2...");
     let (program, payer) = setup_client();
+    let base_mint = program.account::<nfl_blockchain::Market>(market).unwrap().base_mint;
 
     // Step 2: Check order book for available NO token sell orders
     println!("\nStep 2: Checking order book for NO token sell orders...");
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book).unwrap();
+    let (_, orders) = fetch_order_book(&program, order_book);
     
     // Find NO token sell orders (is_yes = false)
-    let no_orders: Vec<_> = ob_account.orders.iter()
-        .filter(|o| !o.is_yes)
+    let no_orders: Vec<_> = orders.iter()
+        .filter(|o| !o.is_yes() && o.side() == nfl_blockchain::Side::Ask)
         .collect();
     
     if no_orders.is_empty() {
@@ -64,7 +52,7 @@ fn main() {
     println!("   [OK] Found {} NO token sell order(s)", no_orders.len());
     for (i, order) in no_orders.iter().enumerate() {
         println!("   Order {}: ID={}, Price={}, Quantity={}", 
-                 i + 1, order.id, order.price, order.quantity);
+                 i + 1, { order.id }, { order.price }, { order.quantity });
     }
 
     // Step 3: Create collateral account and fund it
@@ -93,12 +81,16 @@ fn main() {
             order_book,
             yes_vault,
             no_vault,
+            fee_vault: get_ob_fee_vault_pda(order_book),
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MarketBuy {
             params: nfl_blockchain::MarketBuyParams {
                 quantity: buy_quantity,
                 want_yes: false, // Buying NO tokens
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
             },
         })
         .accounts(vec![AccountMeta::new(user1_collateral, false)])
@@ -107,8 +99,8 @@ fn main() {
     println!("   [OK] Market buy executed: bought {} NO tokens", buy_quantity);
 
     // Verify order was filled
-    let final_ob: nfl_blockchain::OrderBook = program.account(order_book).unwrap();
-    println!("\n   Order book now has {} order(s)", final_ob.orders.len());
+    let (_, final_orders) = fetch_order_book(&program, order_book);
+    println!("\n   Order book now has {} order(s)", final_orders.len());
 
     // Check final balances
     let no_acc: TokenAccount = program.account(user_no).unwrap();
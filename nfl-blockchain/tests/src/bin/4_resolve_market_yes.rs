@@ -1,17 +1,18 @@
 //! Demo 4: Market Authority resolves the market to YES
 //! 
 //! This script demonstrates:
-//! 1. Market Authority resolves the market to YES
+//! 1. Market Authority waits for the market to expire, then resolves it to YES with
+//!    `resolve_market` (the demo market has no challenge period)
 //! 2. Verifies the market resolution
 //!
 //! Usage:
 //!   export ANCHOR_WALLET=~/.config/solana/marketauth.json  # Market Authority's wallet
-//!   export MARKET=<market_address>  # From demo1 output
+//!   export AUTHORITY=<market_authority_wallet>  # From demo1 output
+//!   export EVENT_ID=<event_id>  # From demo1 output
 //!   cargo run --bin demo4
 
 #![allow(deprecated)]
 
-use anchor_client::solana_sdk::pubkey::Pubkey;
 use anchor_client::solana_sdk::signature::Signer;
 
 use tests::test_utils::*;
@@ -20,7 +21,7 @@ fn main() {
     println!("Market Authority resolving market to YES...\n");
 
     // Check for required environment variables
-    let market = get_env_pubkey("MARKET");
+    let market = market_addresses_from_env().market;
 
     // Setup client as Market Authority
     println!("Step 1: Setting up client as Market Authority...");
@@ -34,20 +35,10 @@ fn main() {
     println!("   Market outcome: {:?}", market_before.outcome);
 
     // Step 3: Resolve the market to YES
-    println!("\nStep 3: Resolving market to YES...");
+    println!("\nStep 3: Resolving market to YES once it expires at unix_ts={}...", market_before.expiry_ts);
     let resolution_outcome = nfl_blockchain::Outcome::Yes;
     
-    program
-        .request()
-        .accounts(nfl_blockchain::accounts::ResolveMarket {
-            authority: payer.pubkey(),
-            market,
-        })
-        .args(nfl_blockchain::instruction::ResolveMarket {
-            outcome: resolution_outcome,
-        })
-        .send()
-        .unwrap();
+    resolve_market(&program, payer, market, resolution_outcome).unwrap();
     
     println!("   [OK] Market resolution transaction sent");
 
@@ -66,18 +57,3 @@ fn main() {
 
 
 }
-
-fn get_env_pubkey(name: &str) -> Pubkey {
-    std::env::var(name)
-        .unwrap_or_else(|_| {
-            eprintln!("Error: {} environment variable is not set.", name);
-            eprintln!("Please run demo1 first and export the market information.");
-            std::process::exit(1);
-        })
-        .parse()
-        .unwrap_or_else(|_| {
-            eprintln!("Error: Invalid {} address.", name);
-            std::process::exit(1);
-        })
-}
-
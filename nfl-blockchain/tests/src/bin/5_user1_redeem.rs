@@ -6,17 +6,12 @@
 //!
 //! Usage:
 //!   export ANCHOR_WALLET=~/.config/solana/user1.json  # User 1's wallet
-//!   export MARKET=<market_address>  # From demo1 output
-//!   export BASE_MINT=<base_mint_address>  # From demo1 output
-//!   export YES_MINT=<yes_mint_address>  # From demo1 output
-//!   export NO_MINT=<no_mint_address>  # From demo1 output
-//!   export VAULT=<vault_address>  # From demo1 output
-//!   export MARKET_AUTHORITY=<market_authority_address>  # From demo1 output
+//!   export AUTHORITY=<market_authority_wallet>  # From demo1 output
+//!   export EVENT_ID=<event_id>  # From demo1 output
 //!   cargo run --bin demo5
 
 #![allow(deprecated)]
 
-use anchor_client::solana_sdk::pubkey::Pubkey;
 use anchor_client::solana_sdk::signature::Signer;
 use anchor_spl::token::TokenAccount;
 
@@ -26,16 +21,12 @@ fn main() {
     println!("User 1 Redeeming YES tokens (should succeed)... \n");
 
     // Check for required environment variables
-    let market = get_env_pubkey("MARKET");
-    let base_mint = get_env_pubkey("BASE_MINT");
-    let yes_mint = get_env_pubkey("YES_MINT");
-    let no_mint = get_env_pubkey("NO_MINT");
-    let vault = get_env_pubkey("VAULT");
-    let market_authority = get_env_pubkey("MARKET_AUTHORITY");
+    let MarketAddresses { market, yes_mint, no_mint, vault, market_authority, .. } = market_addresses_from_env();
 
     // Setup client as User 1
     println!("Step 1: Setting up client as User 1...");
     let (program, payer) = setup_client();
+    let base_mint = program.account::<nfl_blockchain::Market>(market).unwrap().base_mint;
     println!("   [OK] User 1 address: {}", payer.pubkey());
 
     // Step 2: Verify market is resolved
@@ -99,8 +90,11 @@ fn main() {
             user_collateral_ata: user_collateral,
             user_yes_ata: user_yes,
             user_no_ata: user_no,
+            recipient_collateral_ata: None,
             market_authority,
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::Redeem {})
         .send();
@@ -130,18 +124,3 @@ fn main() {
         }
     }
 }
-
-fn get_env_pubkey(name: &str) -> Pubkey {
-    std::env::var(name)
-        .unwrap_or_else(|_| {
-            eprintln!("Error: {} environment variable is not set.", name);
-            eprintln!("Please run demo1 first and export the market information.");
-            std::process::exit(1);
-        })
-        .parse()
-        .unwrap_or_else(|_| {
-            eprintln!("Error: Invalid {} address.", name);
-            std::process::exit(1);
-        })
-}
-
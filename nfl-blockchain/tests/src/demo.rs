@@ -10,7 +10,7 @@
 #![allow(deprecated)]
 
 use anchor_client::solana_sdk::{
-    instruction::AccountMeta, pubkey::Pubkey, signer::Signer,
+    instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer,
 };
 use anchor_spl::token::TokenAccount;
 
@@ -29,6 +29,10 @@ fn get_ob_vault_pda(order_book: Pubkey, is_yes: bool) -> Pubkey {
     Pubkey::find_program_address(&[seed, order_book.as_ref()], &nfl_blockchain::id()).0
 }
 
+fn get_ob_collateral_vault_pda(order_book: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"collateral_vault", order_book.as_ref()], &nfl_blockchain::id()).0
+}
+
 pub fn run_demo() {
     println!("NFL Blockchain Demo - Starting...\n");
 
@@ -48,11 +52,11 @@ pub fn run_demo() {
 
     // Step 2: Create a prediction market
     println!("Step 2: Creating prediction market...");
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) =
+    let (market, yes_mint, no_mint, vault, market_authority) =
         create_market(&program, payer, base_mint);
-    println!("   [OK] Market created: {}", market_kp.pubkey());
-    println!("   [OK] YES mint: {}", yes_mint_kp.pubkey());
-    println!("   [OK] NO mint: {}\n", no_mint_kp.pubkey());
+    println!("   [OK] Market created: {}", market);
+    println!("   [OK] YES mint: {}", yes_mint);
+    println!("   [OK] NO mint: {}\n", no_mint);
 
     // Step 3: User mints YES/NO pairs (deposits collateral, receives outcome tokens)
     println!("Step 3: User mints YES/NO token pairs...");
@@ -61,11 +65,11 @@ pub fn run_demo() {
 
     let (user_yes, user_no) = mint_pairs_for_user(
         &program,
-        market_kp.pubkey(),
+        market,
         base_mint,
-        yes_mint_kp.pubkey(),
-        no_mint_kp.pubkey(),
-        vault_kp.pubkey(),
+        yes_mint,
+        no_mint,
+        vault,
         market_authority,
         payer,
         user_collateral,
@@ -77,25 +81,29 @@ pub fn run_demo() {
 
     // Step 4: Initialize order book
     println!("Step 4: Initializing order book...");
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
+    let order_book_pda = get_orderbook_pda(market);
     let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
     let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
 
     program
         .request()
         .accounts(nfl_blockchain::accounts::InitializeOrderBook {
             authority: payer.try_pubkey().unwrap(),
             order_book: order_book_pda,
-            market: market_kp.pubkey(),
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
+            collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
             token_program: anchor_spl::token::spl_token::id(),
             system_program: anchor_client::solana_sdk::system_program::id(),
             rent: anchor_client::solana_sdk::sysvar::rent::id(),
         })
-        .args(nfl_blockchain::instruction::InitializeOrderBook {})
+        .args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 })
         .send()
         .unwrap();
     println!("   [OK] Order book initialized: {}\n", order_book_pda);
@@ -114,53 +122,69 @@ pub fn run_demo() {
             order_book: order_book_pda,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
-            market: market_kp.pubkey(),
+            market,
             token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::PlaceLimitSell {
             price: sell_price,
             quantity: sell_quantity,
             is_yes: true,
+            time_in_force: nfl_blockchain::TimeInForce::Gtc,
+            post_only: None,
+            self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
         })
         .send()
         .unwrap();
     println!("   [OK] Placed sell order: {} YES tokens at price {}\n", sell_quantity, sell_price);
 
     // Verify order was placed
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book_pda).unwrap();
-    println!("   Order book now has {} order(s)", ob_account.orders.len());
-    if let Some(order) = ob_account.orders.first() {
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    println!("   Order book now has {} order(s)", orders.len());
+    if let Some(order) = orders.first() {
         println!("   Order details: ID={}, Price={}, Quantity={}, IsYes={}\n", 
-                 order.id, order.price, order.quantity, order.is_yes);
+                 { order.id }, { order.price }, { order.quantity }, order.is_yes());
     }
 
     // Step 6: Execute market buy
     println!("Step 6: Executing market buy...");
-    let buyer_collateral = user_collateral; // Same user for demo simplicity
-    let buyer_yes = user_yes; // Buyer will receive YES tokens here
-    mint_tokens(&program, payer, base_mint, buyer_collateral, 2000); // Add more collateral
+    // A second wallet buys: self-trade prevention stops a wallet from filling its own orders
+    let buyer = Keypair::new();
+    fund_account(&program, payer, &buyer.pubkey(), 1_000_000_000);
+    let buyer_collateral = create_ata(&program, payer, buyer.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, buyer.pubkey(), yes_mint); // Buyer will receive YES tokens here
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 2000);
 
     let buy_quantity = 10; // Buy 10 YES tokens
 
     program
         .request()
         .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
-            buyer: payer.try_pubkey().unwrap(),
+            buyer: buyer.pubkey(),
             buyer_collateral_ata: buyer_collateral,
             buyer_receive_token_ata: buyer_yes,
-            market: market_kp.pubkey(),
+            market,
             order_book: order_book_pda,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MarketBuy {
             params: nfl_blockchain::MarketBuyParams {
                 quantity: buy_quantity,
                 want_yes: true,
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
             },
         })
         .accounts(vec![AccountMeta::new(user_collateral, false)])
+        .signer(&buyer)
         .send()
         .unwrap();
     println!("   [OK] Market buy executed: bought {} YES tokens\n", buy_quantity);
@@ -169,14 +193,14 @@ pub fn run_demo() {
     println!("Step 7: Verifying final state...");
     let buyer_yes_acc: TokenAccount = program.account(buyer_yes).unwrap();
     let buyer_collateral_acc: TokenAccount = program.account(buyer_collateral).unwrap();
-    let final_ob: nfl_blockchain::OrderBook = program.account(order_book_pda).unwrap();
+    let (_, final_orders) = fetch_order_book(&program, order_book_pda);
 
     println!("   [OK] Buyer YES tokens: {}", buyer_yes_acc.amount);
     println!("   [OK] Buyer collateral: {}", buyer_collateral_acc.amount);
-    println!("   [OK] Remaining orders in book: {}", final_ob.orders.len());
+    println!("   [OK] Remaining orders in book: {}", final_orders.len());
     
-    if let Some(order) = final_ob.orders.first() {
-        println!("   [OK] Remaining order quantity: {}", order.quantity);
+    if let Some(order) = final_orders.first() {
+        println!("   [OK] Remaining order quantity: {}", { order.quantity });
     }
 
     println!("\nDemo completed successfully!");
@@ -1,12 +1,30 @@
 #[cfg(test)]
 mod test_create_market;
 
+#[cfg(test)]
+mod test_token_metadata;
+
 #[cfg(test)]
 mod test_mint_pairs;
 
+#[cfg(test)]
+mod test_merge_pairs;
+
+#[cfg(test)]
+mod test_halt_market;
+
+#[cfg(test)]
+mod test_trading_window;
+
 #[cfg(test)]
 mod test_resolve_market;
 
+#[cfg(test)]
+mod test_optimistic_resolution;
+
+#[cfg(test)]
+mod test_resolution_committee;
+
 #[cfg(test)]
 mod test_redeem;
 
@@ -16,9 +34,33 @@ mod test_redeem_twice;
 #[cfg(test)]
 mod test_loser_cannot_redeem;
 
+#[cfg(test)]
+mod test_close_market;
+
+#[cfg(test)]
+mod test_categorical_market;
+
+#[cfg(test)]
+mod test_scalar_market;
+
 #[cfg(test)]
 mod test_order_book;
 
+#[cfg(test)]
+mod test_time_in_force;
+
+#[cfg(test)]
+mod test_self_trade;
+
+#[cfg(test)]
+mod test_fees;
+
+#[cfg(test)]
+mod test_market_maker;
+
+#[cfg(test)]
+mod test_events;
+
 // Utils
 pub mod test_utils;
 
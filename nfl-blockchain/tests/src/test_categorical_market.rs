@@ -0,0 +1,477 @@
+#![allow(deprecated)]
+
+use anchor_client::{
+    solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer, system_program, sysvar::rent},
+    Program,
+};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+/// Derive the mint PDA for outcome `index` of a categorical market.
+fn get_outcome_mint_pda(market: Pubkey, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"outcome_mint", market.as_ref(), &[index]], &nfl_blockchain::id()).0
+}
+
+/// Derive the collateral vault PDA of a categorical market.
+fn get_categorical_vault_pda(market: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", market.as_ref()], &nfl_blockchain::id()).0
+}
+
+/// (outcome mint, user token account) pairs for `mint_sets` / `merge_sets`.
+fn outcome_set_metas(outcome_mints: &[Pubkey], user_atas: &[Pubkey]) -> Vec<AccountMeta> {
+    outcome_mints
+        .iter()
+        .zip(user_atas)
+        .flat_map(|(mint, ata)| [AccountMeta::new(*mint, false), AccountMeta::new(*ata, false)])
+        .collect()
+}
+
+fn complete_sets_accounts(
+    user: Pubkey,
+    user_collateral_ata: Pubkey,
+    market: Pubkey,
+    vault: Pubkey,
+    market_authority: Pubkey,
+) -> nfl_blockchain::accounts::CompleteSets {
+    nfl_blockchain::accounts::CompleteSets {
+        user,
+        user_collateral_ata,
+        market,
+        vault,
+        market_authority,
+        token_program: spl_token::id(),
+        event_authority: get_event_authority_pda(),
+        program: nfl_blockchain::id(),
+    }
+}
+
+/// Create a categorical market with `num_outcomes` outcomes that trades until `expiry_ts`,
+/// without any outcome mints yet. Returns (market, vault, market_authority).
+fn create_categorical_market_without_mints(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    num_outcomes: u8,
+    expiry_ts: i64,
+) -> (Keypair, Pubkey, Pubkey) {
+    let market = Keypair::new();
+    let vault = get_categorical_vault_pda(market.pubkey());
+    let (market_authority, _bump) =
+        Pubkey::find_program_address(&[b"market_auth", market.pubkey().as_ref()], &program.id());
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::CreateCategoricalMarket {
+            authority: payer.pubkey(),
+            market: market.pubkey(),
+            base_mint,
+            vault,
+            market_authority,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: rent::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CreateCategoricalMarket {
+            expiry_ts,
+            num_outcomes,
+            trading_window: open_until(expiry_ts),
+            resolver: payer.pubkey(),
+        })
+        .signer(&market)
+        .send()
+        .unwrap();
+
+    (market, vault, market_authority)
+}
+
+/// Add the mint for outcome `index` of `market` and return it.
+fn add_outcome_mint(program: &Program<&Keypair>, payer: &Keypair, market: Pubkey, base_mint: Pubkey, index: u8) -> Pubkey {
+    let outcome_mint = get_outcome_mint_pda(market, index);
+    let (market_authority, _bump) = Pubkey::find_program_address(&[b"market_auth", market.as_ref()], &program.id());
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::AddOutcomeMint {
+            authority: payer.pubkey(),
+            market,
+            base_mint,
+            outcome_mint,
+            market_authority,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: rent::id(),
+        })
+        .args(nfl_blockchain::instruction::AddOutcomeMint { index })
+        .send()
+        .unwrap();
+    outcome_mint
+}
+
+/// Create a categorical market with `num_outcomes` outcomes that trades until `expiry_ts`,
+/// and add every outcome mint. Returns (market, vault, market_authority, outcome_mints).
+fn create_categorical_market(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    num_outcomes: u8,
+    expiry_ts: i64,
+) -> (Keypair, Pubkey, Pubkey, Vec<Pubkey>) {
+    let (market, vault, market_authority) =
+        create_categorical_market_without_mints(program, payer, base_mint, num_outcomes, expiry_ts);
+    let outcome_mints = (0..num_outcomes)
+        .map(|index| add_outcome_mint(program, payer, market.pubkey(), base_mint, index))
+        .collect();
+
+    (market, vault, market_authority, outcome_mints)
+}
+
+/// Resolve `market` to `outcome` as `resolver`, once it has expired.
+fn resolve_categorical_market(
+    program: &Program<&Keypair>,
+    resolver: &Keypair,
+    market: Pubkey,
+    outcome: nfl_blockchain::CategoricalOutcome,
+) -> Result<anchor_client::solana_sdk::signature::Signature, anchor_client::ClientError> {
+    wait_until(program, program.account::<nfl_blockchain::CategoricalMarket>(market).unwrap().expiry_ts);
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::ResolveCategoricalMarket {
+            resolver: resolver.pubkey(),
+            market,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::ResolveCategoricalMarket { outcome })
+        .signer(resolver)
+        .send()
+}
+
+#[test]
+fn test_categorical_market_lifecycle() {
+    // Setup: "Which team wins the AFC East?" with four outcomes
+    let (program, payer) = setup_client();
+
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market_kp, vault, market_authority, outcome_mints) =
+        create_categorical_market(&program, payer, base_mint, 4, validator_now(&program) + TEST_MARKET_LIFETIME_SECS);
+
+    let market: nfl_blockchain::CategoricalMarket = program.account(market_kp.pubkey()).unwrap();
+    assert_eq!(market.outcome_mints, outcome_mints);
+    assert_eq!(market.outcome, nfl_blockchain::CategoricalOutcome::Pending);
+    assert_eq!(market.resolver, payer.pubkey());
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+    let user_atas: Vec<Pubkey> = outcome_mints
+        .iter()
+        .map(|mint| create_ata(&program, payer, payer.pubkey(), *mint))
+        .collect();
+
+    let sets = |amount: u64, merge: bool, metas: Vec<AccountMeta>| {
+        let accounts = complete_sets_accounts(
+            payer.pubkey(),
+            user_collateral_ata,
+            market_kp.pubkey(),
+            vault,
+            market_authority,
+        );
+        let request = program.request().accounts(accounts).accounts(metas);
+        if merge {
+            request.args(nfl_blockchain::instruction::MergeSets { amount }).send()
+        } else {
+            request.args(nfl_blockchain::instruction::MintSets { amount }).send()
+        }
+    };
+
+    // A set missing an outcome is rejected
+    let partial = outcome_set_metas(&outcome_mints[..3], &user_atas[..3]);
+    assert!(sets(10, false, partial).is_err(), "Minting requires every outcome");
+
+    // Mint 10 complete sets, then merge 4 back
+    let signature = sets(10, false, outcome_set_metas(&outcome_mints, &user_atas)).unwrap();
+    let minted = fetch_events::<nfl_blockchain::SetsMinted>(&program, &signature);
+    assert_eq!((minted.len(), minted[0].amount), (1, 10));
+    let signature = sets(4, true, outcome_set_metas(&outcome_mints, &user_atas)).unwrap();
+    let merged = fetch_events::<nfl_blockchain::SetsMerged>(&program, &signature);
+    assert_eq!((merged.len(), merged[0].amount), (1, 4));
+
+    for ata in &user_atas {
+        assert_eq!(program.account::<TokenAccount>(*ata).unwrap().amount, 6);
+    }
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 6);
+    assert_eq!(program.account::<TokenAccount>(user_collateral_ata).unwrap().amount, 94);
+
+    // Halting stops minting but not merging
+    let set_halted = |halted: bool| {
+        let request = program
+            .request()
+            .accounts(nfl_blockchain::accounts::UpdateCategoricalMarketStatus {
+                authority: payer.pubkey(),
+                market: market_kp.pubkey(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            });
+        if halted {
+            request.args(nfl_blockchain::instruction::HaltCategoricalMarket {}).send()
+        } else {
+            request.args(nfl_blockchain::instruction::ResumeCategoricalMarket {}).send()
+        }
+    };
+    set_halted(true).unwrap();
+    assert!(
+        sets(1, false, outcome_set_metas(&outcome_mints, &user_atas)).is_err(),
+        "Minting should be rejected while halted"
+    );
+    sets(1, true, outcome_set_metas(&outcome_mints, &user_atas)).unwrap();
+    set_halted(false).unwrap();
+    sets(1, false, outcome_set_metas(&outcome_mints, &user_atas)).unwrap();
+
+    // Only the resolver settles the market, and only to an outcome that exists
+    let outsider = Keypair::new();
+    assert!(
+        resolve_categorical_market(&program, &outsider, market_kp.pubkey(), nfl_blockchain::CategoricalOutcome::Winner(2)).is_err(),
+        "Only the resolver may resolve"
+    );
+    assert!(
+        resolve_categorical_market(&program, payer, market_kp.pubkey(), nfl_blockchain::CategoricalOutcome::Winner(4)).is_err(),
+        "Outcome index out of range"
+    );
+
+    // Resolve to outcome 2
+    let signature =
+        resolve_categorical_market(&program, payer, market_kp.pubkey(), nfl_blockchain::CategoricalOutcome::Winner(2))
+            .unwrap();
+    let resolved = fetch_events::<nfl_blockchain::CategoricalMarketResolved>(&program, &signature);
+    assert_eq!(resolved.len(), 1);
+    assert!(resolved[0].outcome == nfl_blockchain::CategoricalOutcome::Winner(2));
+
+    assert!(
+        sets(1, false, outcome_set_metas(&outcome_mints, &user_atas)).is_err(),
+        "Minting should be rejected once resolved"
+    );
+
+    let redeem = |index: usize| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::RedeemCategorical {
+                user: payer.pubkey(),
+                market: market_kp.pubkey(),
+                outcome_mint: outcome_mints[index],
+                vault,
+                user_collateral_ata,
+                user_outcome_ata: user_atas[index],
+                recipient_collateral_ata: None,
+                market_authority,
+                token_program: spl_token::id(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::RedeemCategorical { amount: None })
+            .send()
+    };
+
+    // Losing outcomes cannot be redeemed; the winner pays 1:1
+    assert!(redeem(0).is_err(), "Losing outcome should not redeem");
+    let signature = redeem(2).unwrap();
+    let redeemed = fetch_events::<nfl_blockchain::CategoricalRedeemed>(&program, &signature);
+    assert_eq!(redeemed.len(), 1);
+    assert_eq!((redeemed[0].recipient, redeemed[0].burned, redeemed[0].payout), (user_collateral_ata, 6, 6));
+
+    assert_eq!(program.account::<TokenAccount>(user_atas[2]).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(user_atas[0]).unwrap().amount, 6);
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(user_collateral_ata).unwrap().amount, 100);
+}
+
+#[test]
+fn test_categorical_market_max_outcomes() {
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    // One outcome more than a complete set transaction can carry is rejected
+    let market = Keypair::new();
+    let vault = get_categorical_vault_pda(market.pubkey());
+    let (market_authority, _bump) =
+        Pubkey::find_program_address(&[b"market_auth", market.pubkey().as_ref()], &program.id());
+    let too_many = program
+        .request()
+        .accounts(nfl_blockchain::accounts::CreateCategoricalMarket {
+            authority: payer.pubkey(),
+            market: market.pubkey(),
+            base_mint,
+            vault,
+            market_authority,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: rent::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CreateCategoricalMarket {
+            expiry_ts: i64::MAX,
+            num_outcomes: nfl_blockchain::CategoricalMarket::MAX_OUTCOMES + 1,
+            trading_window: ALWAYS_OPEN,
+            resolver: payer.pubkey(),
+        })
+        .signer(&market)
+        .send();
+    assert!(too_many.is_err(), "More than MAX_OUTCOMES outcomes should be rejected");
+
+    // At the maximum, complete sets still fit in a single transaction both ways
+    let (market_kp, vault, market_authority, outcome_mints) = create_categorical_market(
+        &program,
+        payer,
+        base_mint,
+        nfl_blockchain::CategoricalMarket::MAX_OUTCOMES,
+        i64::MAX,
+    );
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 10);
+    let user_atas: Vec<Pubkey> = outcome_mints
+        .iter()
+        .map(|mint| create_ata(&program, payer, payer.pubkey(), *mint))
+        .collect();
+
+    let accounts = || {
+        complete_sets_accounts(
+            payer.pubkey(),
+            user_collateral_ata,
+            market_kp.pubkey(),
+            vault,
+            market_authority,
+        )
+    };
+    program
+        .request()
+        .accounts(accounts())
+        .accounts(outcome_set_metas(&outcome_mints, &user_atas))
+        .args(nfl_blockchain::instruction::MintSets { amount: 10 })
+        .send()
+        .unwrap();
+    for ata in &user_atas {
+        assert_eq!(program.account::<TokenAccount>(*ata).unwrap().amount, 10);
+    }
+
+    program
+        .request()
+        .accounts(accounts())
+        .accounts(outcome_set_metas(&outcome_mints, &user_atas))
+        .args(nfl_blockchain::instruction::MergeSets { amount: 10 })
+        .send()
+        .unwrap();
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(user_collateral_ata).unwrap().amount, 10);
+}
+
+#[test]
+fn test_categorical_market_invalid_refunds_pro_rata() {
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market_kp, vault, market_authority, outcome_mints) =
+        create_categorical_market(&program, payer, base_mint, 3, validator_now(&program) + TEST_MARKET_LIFETIME_SECS);
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 10);
+    let user_atas: Vec<Pubkey> = outcome_mints
+        .iter()
+        .map(|mint| create_ata(&program, payer, payer.pubkey(), *mint))
+        .collect();
+
+    let accounts = || {
+        complete_sets_accounts(
+            payer.pubkey(),
+            user_collateral_ata,
+            market_kp.pubkey(),
+            vault,
+            market_authority,
+        )
+    };
+    program
+        .request()
+        .accounts(accounts())
+        .accounts(outcome_set_metas(&outcome_mints, &user_atas))
+        .args(nfl_blockchain::instruction::MintSets { amount: 10 })
+        .send()
+        .unwrap();
+
+    // Void the market
+    resolve_categorical_market(&program, payer, market_kp.pubkey(), nfl_blockchain::CategoricalOutcome::Invalid).unwrap();
+
+    // No outcome wins, so none redeems 1:1
+    let redeem_winner = program
+        .request()
+        .accounts(nfl_blockchain::accounts::RedeemCategorical {
+            user: payer.pubkey(),
+            market: market_kp.pubkey(),
+            outcome_mint: outcome_mints[0],
+            vault,
+            user_collateral_ata,
+            user_outcome_ata: user_atas[0],
+            recipient_collateral_ata: None,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::RedeemCategorical { amount: None })
+        .send();
+    assert!(redeem_winner.is_err(), "A voided market has no winning outcome");
+
+    // Every outcome pays 1/3: 30 outcome tokens refund the 10 deposited
+    program
+        .request()
+        .accounts(accounts())
+        .accounts(outcome_set_metas(&outcome_mints, &user_atas))
+        .args(nfl_blockchain::instruction::RefundCategorical {})
+        .send()
+        .unwrap();
+
+    for ata in &user_atas {
+        assert_eq!(program.account::<TokenAccount>(*ata).unwrap().amount, 0);
+    }
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(user_collateral_ata).unwrap().amount, 10);
+}
+
+#[test]
+fn test_categorical_market_requires_every_outcome_mint() {
+    // Setup: three outcomes, but only two of their mints are added
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market_kp, vault, market_authority) = create_categorical_market_without_mints(
+        &program,
+        payer,
+        base_mint,
+        3,
+        validator_now(&program) + TEST_MARKET_LIFETIME_SECS,
+    );
+    let outcome_mints: Vec<Pubkey> =
+        (0..2).map(|index| add_outcome_mint(&program, payer, market_kp.pubkey(), base_mint, index)).collect();
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 10);
+    let user_atas: Vec<Pubkey> = outcome_mints
+        .iter()
+        .map(|mint| create_ata(&program, payer, payer.pubkey(), *mint))
+        .collect();
+
+    // No complete set can be minted...
+    let minted = program
+        .request()
+        .accounts(complete_sets_accounts(payer.pubkey(), user_collateral_ata, market_kp.pubkey(), vault, market_authority))
+        .args(nfl_blockchain::instruction::MintSets { amount: 10 })
+        .accounts(outcome_set_metas(&outcome_mints, &user_atas))
+        .send();
+    assert_program_error(minted, nfl_blockchain::NflError::OutcomeMintsIncomplete);
+
+    // ...and no outcome can win, not even one whose mint exists
+    assert_program_error(
+        resolve_categorical_market(&program, payer, market_kp.pubkey(), nfl_blockchain::CategoricalOutcome::Winner(0)),
+        nfl_blockchain::NflError::OutcomeMintsIncomplete,
+    );
+    let market: nfl_blockchain::CategoricalMarket = program.account(market_kp.pubkey()).unwrap();
+    assert!(market.status == nfl_blockchain::MarketStatus::Open);
+}
@@ -0,0 +1,190 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_close_market_and_order_book() {
+    // Setup: a market with an order book, resolved as a tie so redemption leaves dust,
+    // and a maker whose GTC bid is still resting at resolution
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) =
+        initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+    let fee_vault = get_ob_fee_vault_pda(order_book);
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+
+    // Leftovers go to a separate treasury wallet
+    let treasury = Keypair::new();
+    let treasury_collateral_ata = create_ata(&program, payer, treasury.pubkey(), base_mint);
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::SetTreasury { authority: payer.pubkey(), market })
+        .args(nfl_blockchain::instruction::SetTreasury { treasury: treasury.pubkey() })
+        .send()
+        .unwrap();
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 3);
+    let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral_ata,
+        3,
+    );
+
+    let maker = Keypair::new();
+    fund_account(&program, payer, &maker.pubkey(), 1_000_000_000);
+    let maker_collateral = create_ata(&program, payer, maker.pubkey(), base_mint);
+    let maker_yes = create_ata(&program, payer, maker.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, maker_collateral, 100);
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: maker.pubkey(),
+            buyer_collateral_ata: maker_collateral,
+            buyer_receive_token_ata: maker_yes,
+            order_book,
+            collateral_vault,
+            yes_vault,
+            no_vault,
+            fee_vault,
+            market,
+            token_program: spl_token::id(),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy {
+            price: 20,
+            quantity: 50,
+            is_yes: true,
+            time_in_force: nfl_blockchain::TimeInForce::Gtc,
+            post_only: None,
+            self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+        })
+        .signer(&maker)
+        .send()
+        .unwrap();
+    let (_, orders) = fetch_order_book(&program, order_book);
+    let resting_order_id = orders[0].id;
+
+    // Anyone may purge a resting order once the market is resolved
+    let purge_resolved_order = |owner_token_ata| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::PurgeOrder {
+                owner_token_ata,
+                market,
+                order_book,
+                yes_vault,
+                no_vault,
+                collateral_vault,
+                token_program: spl_token::id(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PurgeResolvedOrder { order_id: resting_order_id })
+            .send()
+    };
+
+    let close_order_book = |treasury_collateral_ata| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::CloseOrderBook {
+                authority: payer.pubkey(),
+                market,
+                order_book,
+                yes_vault,
+                no_vault,
+                collateral_vault,
+                fee_vault,
+                treasury_collateral_ata,
+                token_program: spl_token::id(),
+            })
+            .args(nfl_blockchain::instruction::CloseOrderBook {})
+            .send()
+    };
+    let close_market = |with_proposal: bool| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::CloseMarket {
+                authority: payer.pubkey(),
+                market,
+                yes_mint,
+                no_mint,
+                vault,
+                metadata: get_market_metadata_pda(market),
+                order_book,
+                proposal: with_proposal.then_some(proposal),
+                bond_vault: with_proposal.then_some(bond_vault),
+                treasury_collateral_ata,
+                market_authority,
+                token_program: spl_token::id(),
+            })
+            .args(nfl_blockchain::instruction::CloseMarket {})
+            .send()
+    };
+
+    assert!(close_order_book(treasury_collateral_ata).is_err(), "Only resolved markets can be closed");
+    assert!(purge_resolved_order(maker_collateral).is_err(), "Market is not resolved");
+    resolve_market(&program, payer, market, nfl_blockchain::Outcome::Tie).unwrap();
+
+    // The maker's bid keeps the book open until it is purged back to them
+    assert!(close_order_book(treasury_collateral_ata).is_err(), "An order is still resting");
+    assert!(purge_resolved_order(user_collateral_ata).is_err(), "Escrow goes back to the order's owner");
+    purge_resolved_order(maker_collateral).unwrap();
+    assert_eq!(program.account::<TokenAccount>(maker_collateral).unwrap().amount, 100);
+    assert!(fetch_order_book(&program, order_book).1.is_empty());
+
+    // The market must be closed after its book, and only once redeemed
+    assert!(close_market(true).is_err(), "Order book is still open");
+    assert!(close_order_book(user_collateral_ata).is_err(), "Leftovers must go to the treasury");
+    close_order_book(treasury_collateral_ata).unwrap();
+    assert!(program.rpc().get_account(&order_book).is_err());
+    assert!(program.rpc().get_account(&yes_vault).is_err());
+    assert!(close_market(true).is_err(), "Tokens are still unredeemed");
+
+    // A tie pays 1 for each side of 3 pairs, leaving 1 unit of rounding dust in the vault
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::Redeem {
+            user: payer.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            user_collateral_ata,
+            user_yes_ata,
+            user_no_ata,
+            recipient_collateral_ata: None,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::Redeem {})
+        .send()
+        .unwrap();
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 1);
+
+    let rent_before = program.rpc().get_balance(&payer.pubkey()).unwrap();
+    assert!(close_market(false).is_err(), "The proposal and bond vault must be closed too");
+    close_market(true).unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(treasury_collateral_ata).unwrap().amount, 1);
+    for closed in [market, vault, get_market_metadata_pda(market), proposal, bond_vault] {
+        assert!(program.rpc().get_account(&closed).is_err(), "Account should be closed");
+    }
+    assert!(program.rpc().get_balance(&payer.pubkey()).unwrap() > rent_before, "Rent is refunded");
+}
@@ -1,4 +1,4 @@
-use anchor_client::solana_sdk::signer::Signer;
+use anchor_client::solana_sdk::{pubkey::Pubkey, signer::Signer};
 
 use crate::test_utils::*;
 
@@ -9,18 +9,107 @@ fn test_create_market() {
     let base_mint_kp = create_mint(&program, payer);
     let base_mint = base_mint_kp.pubkey();
 
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, _market_authority) =
+    let (market, yes_mint, no_mint, vault, _market_authority) =
         create_market(&program, payer, base_mint);
 
-    let market_account: nfl_blockchain::Market = program.account(market_kp.pubkey()).unwrap();
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
 
     assert_eq!(market_account.base_mint, base_mint);
-    assert_eq!(market_account.yes_mint, yes_mint_kp.pubkey());
-    assert_eq!(market_account.no_mint, no_mint_kp.pubkey());
-    assert_eq!(market_account.vault, vault_kp.pubkey());
+    assert_eq!(market_account.yes_mint, yes_mint);
+    assert_eq!(market_account.no_mint, no_mint);
+    assert_eq!(market_account.vault, vault);
     assert_eq!(market_account.authority, payer.pubkey());
     assert_eq!(market_account.status, nfl_blockchain::MarketStatus::Open);
     assert_eq!(market_account.outcome, nfl_blockchain::Outcome::Pending);
 
+    let metadata: nfl_blockchain::MarketMetadata =
+        program.account(get_market_metadata_pda(market)).unwrap();
+    let expected = sample_metadata();
+    assert_eq!(metadata.market, market);
+    assert_eq!(metadata.title, expected.title);
+
+    // Every address is derived from (authority, event_id)
+    let addresses = MarketAddresses::derive(payer.pubkey(), &metadata.event_id);
+    assert_eq!(addresses.market, market);
+    assert_eq!(addresses.yes_mint, yes_mint);
+    assert_eq!(addresses.no_mint, no_mint);
+    assert_eq!(addresses.vault, vault);
+    assert_eq!(metadata.home_team, "BUF");
+    assert_eq!(metadata.away_team, "KC");
+    assert_eq!(metadata.kickoff_ts, expected.kickoff_ts);
+
     println!("create_market test passed!");
 }
+
+#[test]
+fn test_update_market_metadata() {
+    let (program, payer) = setup_client();
+
+    let base_mint = create_mint(&program, payer).pubkey();
+    let update = |market: Pubkey, metadata: nfl_blockchain::MarketMetadataParams| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::UpdateMarketMetadata {
+                authority: payer.pubkey(),
+                market,
+                metadata: get_market_metadata_pda(market),
+            })
+            .args(nfl_blockchain::instruction::UpdateMarketMetadata { metadata })
+            .send()
+    };
+    let params_for = |market: Pubkey| {
+        let metadata: nfl_blockchain::MarketMetadata = program.account(get_market_metadata_pda(market)).unwrap();
+        nfl_blockchain::MarketMetadataParams { event_id: metadata.event_id, ..sample_metadata() }
+    };
+
+    // A market whose trading window has not opened yet can still be edited
+    let upcoming = nfl_blockchain::TradingWindow { open_ts: i64::MAX - 1, close_ts: i64::MAX };
+    let (market, ..) = create_market_with_window(&program, payer, base_mint, upcoming);
+
+    // Kickoff moved before trading started
+    let rescheduled = nfl_blockchain::MarketMetadataParams { kickoff_ts: 1_700_003_600, ..params_for(market) };
+    update(market, rescheduled.clone()).unwrap();
+
+    let metadata: nfl_blockchain::MarketMetadata = program.account(get_market_metadata_pda(market)).unwrap();
+    assert_eq!(metadata.kickoff_ts, 1_700_003_600);
+
+    // Fields must fit the account
+    let too_long = nfl_blockchain::MarketMetadataParams { home_team: "BUFFALO".to_string(), ..params_for(market) };
+    assert!(update(market, too_long).is_err(), "Over-long team code should be rejected");
+
+    // The event id fixes the market's address
+    assert!(update(market, sample_metadata()).is_err(), "Event id should not change");
+
+    // Once the trading window opens the description is locked, even before anything is minted
+    let (open_market, ..) = create_market(&program, payer, base_mint);
+    assert!(
+        update(open_market, params_for(open_market)).is_err(),
+        "Metadata should be locked once trading starts"
+    );
+}
+
+#[test]
+fn test_duplicate_event_rejected() {
+    let (program, payer) = setup_client();
+
+    let base_mint = create_mint(&program, payer).pubkey();
+    // The event id keys one proposition on a game, e.g. its moneyline
+    let game_id = &unique_event_id()[..24];
+    let event_id = format!("{game_id}_ML");
+
+    let (market, ..) = create_market_for_event(&program, payer, base_mint, &event_id, i64::MAX).unwrap();
+    assert_eq!(market, MarketAddresses::derive(payer.pubkey(), &event_id).market);
+
+    assert!(
+        create_market_for_event(&program, payer, base_mint, &event_id, i64::MAX).is_err(),
+        "A second market for the same event should be rejected"
+    );
+
+    // Other propositions on the same game get their own keys and markets
+    let spread_id = format!("{game_id}_SPR_-3.5");
+    let (spread_market, ..) = create_market_for_event(&program, payer, base_mint, &spread_id, i64::MAX).unwrap();
+    assert_ne!(spread_market, market);
+
+    // An empty event id cannot identify a market
+    assert!(create_market_for_event(&program, payer, base_mint, "", i64::MAX).is_err());
+}
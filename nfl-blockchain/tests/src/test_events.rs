@@ -0,0 +1,308 @@
+use anchor_client::solana_sdk::{instruction::AccountMeta, signature::Keypair, signer::Signer};
+use anchor_spl::token::spl_token;
+
+use crate::test_utils::*;
+
+#[test]
+fn test_events_are_emitted() {
+    // Setup: a seller mints pairs and offers YES, the payer buys some and redeems them
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) =
+        initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+
+    let seller = Keypair::new();
+    fund_account(&program, payer, &seller.pubkey(), 1_000_000_000);
+    let seller_collateral = create_ata(&program, payer, seller.pubkey(), base_mint);
+    let seller_yes = create_ata(&program, payer, seller.pubkey(), yes_mint);
+    let seller_no = create_ata(&program, payer, seller.pubkey(), no_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 10);
+
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MintPairs {
+            user: seller.pubkey(),
+            user_collateral_ata: seller_collateral,
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            user_yes_ata: seller_yes,
+            user_no_ata: seller_no,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MintPairs { amount: 10 })
+        .signer(&seller)
+        .send()
+        .unwrap();
+
+    let minted = fetch_events::<nfl_blockchain::PairsMinted>(&program, &signature);
+    assert_eq!(minted.len(), 1);
+    assert_eq!(minted[0].market, market);
+    assert_eq!(minted[0].user, seller.pubkey());
+    assert_eq!(minted[0].amount, 10);
+
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: seller.pubkey(),
+            seller_token_ata: seller_yes,
+            seller_receive_collateral_ata: seller_collateral,
+            order_book,
+            yes_vault,
+            no_vault,
+            market,
+            collateral_vault,
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            token_program: spl_token::id(),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell {
+            price: 50,
+            quantity: 10,
+            is_yes: true,
+            time_in_force: nfl_blockchain::TimeInForce::Gtc,
+            post_only: None,
+            self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+        })
+        .signer(&seller)
+        .send()
+        .unwrap();
+
+    let placed = fetch_events::<nfl_blockchain::OrderPlaced>(&program, &signature);
+    assert_eq!(placed.len(), 1);
+    assert_eq!(placed[0].owner, seller.pubkey());
+    assert!(placed[0].side == nfl_blockchain::Side::Ask);
+    assert!(placed[0].is_yes);
+    assert_eq!((placed[0].price, placed[0].quantity), (50, 10));
+    let order_id = placed[0].order_id;
+
+    // The payer takes part of the ask
+    let buyer_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, payer.pubkey(), yes_mint);
+    let buyer_no = create_ata(&program, payer, payer.pubkey(), no_mint);
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 10);
+
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: buyer_collateral,
+            buyer_receive_token_ata: buyer_yes,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest },
+        })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .send()
+        .unwrap();
+
+    let fills = fetch_events::<nfl_blockchain::OrderFilled>(&program, &signature);
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].order_id, order_id);
+    assert_eq!(fills[0].maker, seller.pubkey());
+    assert_eq!(fills[0].taker, payer.pubkey());
+    assert!(fills[0].maker_side == nfl_blockchain::Side::Ask);
+    assert_eq!((fills[0].price, fills[0].quantity, fills[0].cost), (50, 10, 5));
+
+    // Resolution is reported by whichever instruction settles the market
+    wait_for_expiry(&program, market);
+    propose_resolution(&program, payer, market, nfl_blockchain::Outcome::Yes).unwrap();
+    let signature = finalize_resolution(&program, market).unwrap();
+
+    let resolved = fetch_events::<nfl_blockchain::MarketResolved>(&program, &signature);
+    assert_eq!(resolved.len(), 1);
+    assert!(resolved[0].outcome == nfl_blockchain::Outcome::Yes);
+    assert_eq!((resolved[0].payout_yes_bps, resolved[0].payout_no_bps), (10_000, 0));
+
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::Redeem {
+            user: payer.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            user_collateral_ata: buyer_collateral,
+            user_yes_ata: buyer_yes,
+            user_no_ata: buyer_no,
+            recipient_collateral_ata: None,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::Redeem {})
+        .send()
+        .unwrap();
+
+    let redeemed = fetch_events::<nfl_blockchain::Redeemed>(&program, &signature);
+    assert_eq!(redeemed.len(), 1);
+    assert_eq!(redeemed[0].user, payer.pubkey());
+    assert_eq!(redeemed[0].recipient, buyer_collateral);
+    assert_eq!((redeemed[0].yes_amount, redeemed[0].no_amount, redeemed[0].payout), (10, 0, 10));
+}
+
+#[test]
+fn test_state_change_events_are_emitted() {
+    // Setup: the payer mints pairs on a market with an order book
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) =
+        initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+
+    let user_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral, 10);
+    let (user_yes, user_no) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral,
+        10,
+    );
+
+    // An order cancelled by its owner reports what it returned
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: payer.pubkey(),
+            seller_token_ata: user_yes,
+            seller_receive_collateral_ata: user_collateral,
+            order_book,
+            yes_vault,
+            no_vault,
+            market,
+            collateral_vault,
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            token_program: spl_token::id(),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell {
+            price: 50,
+            quantity: 10,
+            is_yes: true,
+            time_in_force: nfl_blockchain::TimeInForce::Gtc,
+            post_only: None,
+            self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+        })
+        .send()
+        .unwrap();
+    let (_, orders) = fetch_order_book(&program, order_book);
+    let order_id = orders[0].id;
+
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::CancelOrder {
+            owner: payer.pubkey(),
+            owner_token_ata: user_yes,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            collateral_vault,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelOrder { order_id })
+        .send()
+        .unwrap();
+
+    let cancelled = fetch_events::<nfl_blockchain::OrderCancelled>(&program, &signature);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!((cancelled[0].order_id, cancelled[0].owner), (order_id, payer.pubkey()));
+    assert!(cancelled[0].side == nfl_blockchain::Side::Ask && cancelled[0].is_yes);
+    assert_eq!((cancelled[0].price, cancelled[0].quantity, cancelled[0].refund), (50, 10, 10));
+    assert!(cancelled[0].reason == nfl_blockchain::CancelReason::Owner);
+
+    // Halting and resuming report the new status
+    let set_status = |halt: bool| {
+        let request = program.request().accounts(nfl_blockchain::accounts::UpdateMarketStatus {
+            authority: payer.pubkey(),
+            market,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        });
+        let signature = if halt {
+            request.args(nfl_blockchain::instruction::HaltMarket {}).send().unwrap()
+        } else {
+            request.args(nfl_blockchain::instruction::ResumeMarket {}).send().unwrap()
+        };
+        let changed = fetch_events::<nfl_blockchain::MarketStatusChanged>(&program, &signature);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].market, market);
+        changed[0].status
+    };
+    assert!(set_status(true) == nfl_blockchain::MarketStatus::Halted);
+    assert!(set_status(false) == nfl_blockchain::MarketStatus::Open);
+
+    // Merging pairs back into collateral
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MergePairs {
+            user: payer.pubkey(),
+            user_collateral_ata: user_collateral,
+            market,
+            yes_mint,
+            no_mint,
+            vault,
+            user_yes_ata: user_yes,
+            user_no_ata: user_no,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MergePairs { amount: 10 })
+        .send()
+        .unwrap();
+
+    let merged = fetch_events::<nfl_blockchain::PairsMerged>(&program, &signature);
+    assert_eq!(merged.len(), 1);
+    assert_eq!((merged[0].market, merged[0].user, merged[0].amount), (market, payer.pubkey(), 10));
+
+    // Fee changes
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::SetFees {
+            authority: payer.pubkey(),
+            market,
+            order_book,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::SetFees { maker_fee_bps: -10, taker_fee_bps: 30 })
+        .send()
+        .unwrap();
+
+    let fees = fetch_events::<nfl_blockchain::FeesSet>(&program, &signature);
+    assert_eq!(fees.len(), 1);
+    assert_eq!((fees[0].maker_fee_bps, fees[0].taker_fee_bps), (-10, 30));
+}
@@ -0,0 +1,208 @@
+use anchor_client::solana_sdk::{instruction::AccountMeta, signature::Keypair, signer::Signer};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_maker_taker_fees() {
+    // Setup: a book charging takers 30 bps and paying makers a 10 bps rebate
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) = initialize_order_book(
+        &program,
+        payer,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+    );
+    let fee_vault = get_ob_fee_vault_pda(order_book);
+
+    let set_fees = |maker_fee_bps: i16, taker_fee_bps: u16| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::SetFees {
+                authority: payer.pubkey(),
+                market,
+                order_book,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::SetFees { maker_fee_bps, taker_fee_bps })
+            .send()
+    };
+    assert!(set_fees(-40, 30).is_err(), "A rebate larger than the taker fee should be rejected");
+    set_fees(-10, 30).unwrap();
+
+    // --- MAKER SETUP ---
+    let maker_kp = Keypair::new();
+    fund_account(&program, payer, &maker_kp.pubkey(), 1_000_000_000);
+    let maker_collateral = create_ata(&program, payer, maker_kp.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, maker_collateral, 110_010);
+    let (maker_yes, _maker_no) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        &maker_kp,
+        maker_collateral,
+        110_010,
+    );
+
+    // Maker rests an ask for 100_000 YES at 0.50 (cost 50_000)
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: maker_kp.pubkey(),
+            seller_token_ata: maker_yes,
+            seller_receive_collateral_ata: maker_collateral,
+            order_book,
+            yes_vault,
+            no_vault,
+            market,
+            token_program: spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book),
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 100_000, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .signer(&maker_kp)
+        .send()
+        .unwrap();
+
+    // --- TAKER BUYS ---
+    let taker_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    let taker_yes = create_ata(&program, payer, payer.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, taker_collateral, 60_000);
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: taker_collateral,
+            buyer_receive_token_ata: taker_yes,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            fee_vault,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams { quantity: 100_000, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(maker_collateral, false)])
+        .send()
+        .unwrap();
+
+    // Taker pays 50_000 + 150 fee; maker receives 50_000 + 50 rebate; the book keeps 100
+    assert_eq!(program.account::<TokenAccount>(taker_collateral).unwrap().amount, 60_000 - 50_150);
+    assert_eq!(program.account::<TokenAccount>(maker_collateral).unwrap().amount, 50_050);
+    assert_eq!(program.account::<TokenAccount>(fee_vault).unwrap().amount, 100);
+
+    // --- BID SIDE ---
+    // A bid escrows its cost (4_004) less the maker rebate (4, rounded down)
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: taker_collateral,
+            buyer_receive_token_ata: taker_yes,
+            order_book,
+            collateral_vault,
+            market,
+            token_program: spl_token::id(),
+            yes_vault: get_ob_vault_pda(order_book, true),
+            no_vault: get_ob_vault_pda(order_book, false),
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: 40, quantity: 10_010, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send()
+        .unwrap();
+    assert_eq!(program.account::<TokenAccount>(collateral_vault).unwrap().amount, 4_000);
+
+    // The maker now takes: selling 10_010 YES into the bid costs them the taker fee,
+    // 12.012 rounded up to 13
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::MarketSellAccounts {
+            seller: maker_kp.pubkey(),
+            seller_token_ata: maker_yes,
+            seller_receive_collateral_ata: maker_collateral,
+            market,
+            order_book,
+            collateral_vault,
+            fee_vault,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketSell {
+            params: nfl_blockchain::MarketSellParams { quantity: 10_010, sell_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(taker_yes, false)])
+        .signer(&maker_kp)
+        .send()
+        .unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(maker_collateral).unwrap().amount, 50_050 + 3_991);
+    assert_eq!(program.account::<TokenAccount>(collateral_vault).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(fee_vault).unwrap().amount, 109);
+
+    // --- WITHDRAW ---
+    let withdraw = |authority: &Keypair, amount: u64| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::WithdrawFees {
+                authority: authority.pubkey(),
+                market,
+                order_book,
+                fee_vault,
+                recipient_collateral_ata: taker_collateral,
+                token_program: spl_token::id(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::WithdrawFees { amount })
+            .signer(authority)
+            .send()
+    };
+    assert!(withdraw(&maker_kp, 109).is_err(), "Only the market authority may withdraw fees");
+    let signature = withdraw(payer, 109).unwrap();
+
+    let withdrawn = fetch_events::<nfl_blockchain::FeesWithdrawn>(&program, &signature);
+    assert_eq!(withdrawn.len(), 1);
+    assert_eq!((withdrawn[0].recipient, withdrawn[0].amount), (taker_collateral, 109));
+
+    assert_eq!(program.account::<TokenAccount>(fee_vault).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(taker_collateral).unwrap().amount, 60_000 - 50_150 - 4_000 + 109);
+}
+
+#[test]
+fn test_dmm_maker_fee_rates() {
+    use nfl_blockchain::dmm_maker_fee_bps;
+
+    // No benefits: the book's rate applies as is
+    assert_eq!(dmm_maker_fee_bps(10, 40, 0, 0), 10);
+    // Fee tiers discount a positive maker fee, down to nothing at the top tier
+    assert_eq!(dmm_maker_fee_bps(10, 40, 2, 0), 5);
+    assert_eq!(dmm_maker_fee_bps(10, 40, 4, 0), 0);
+    // ...but never a rebate
+    assert_eq!(dmm_maker_fee_bps(-10, 40, 4, 0), -10);
+    // The rebate share hands the maker a quarter of the 40 bps taker fee
+    assert_eq!(dmm_maker_fee_bps(10, 40, 2, 2_500), -5);
+    // The total rebate is capped at the taker fee that funds it
+    assert_eq!(dmm_maker_fee_bps(-30, 40, 0, 10_000), -40);
+}
@@ -0,0 +1,172 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_halt_and_resume_market() {
+    // Setup
+    let (program, payer) = setup_client();
+
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, _collateral_vault) = initialize_order_book(
+        &program,
+        payer,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+    );
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+    let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral_ata,
+        20,
+    );
+
+    // Only the market authority may halt
+    let stranger = Keypair::new();
+    fund_account(&program, payer, &stranger.pubkey(), 1_000_000_000);
+    let result = program
+        .request()
+        .accounts(nfl_blockchain::accounts::UpdateMarketStatus {
+            authority: stranger.pubkey(),
+            market,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::HaltMarket {})
+        .signer(&stranger)
+        .send();
+    assert!(result.is_err(), "Non-authority should not be able to halt");
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::UpdateMarketStatus {
+            authority: payer.pubkey(),
+            market,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::HaltMarket {})
+        .send()
+        .unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert_eq!(market_account.status, nfl_blockchain::MarketStatus::Halted);
+
+    // Minting and placing orders are rejected while halted
+    let result = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MintPairs {
+            user: payer.pubkey(),
+            user_collateral_ata,
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            user_yes_ata,
+            user_no_ata,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MintPairs { amount: 1 })
+        .send();
+    assert!(result.is_err(), "Minting should fail while halted");
+
+    let result = program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: payer.pubkey(),
+            seller_token_ata: user_yes_ata,
+            seller_receive_collateral_ata: user_collateral_ata,
+            order_book,
+            yes_vault,
+            no_vault,
+            market,
+            token_program: spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book),
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send();
+    assert!(result.is_err(), "Placing orders should fail while halted");
+
+    // Merging is still allowed while halted
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::MergePairs {
+            user: payer.pubkey(),
+            user_collateral_ata,
+            market,
+            yes_mint,
+            no_mint,
+            vault,
+            user_yes_ata,
+            user_no_ata,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MergePairs { amount: 5 })
+        .send()
+        .unwrap();
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 15);
+
+    // Resume and trade again
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::UpdateMarketStatus {
+            authority: payer.pubkey(),
+            market,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::ResumeMarket {})
+        .send()
+        .unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert_eq!(market_account.status, nfl_blockchain::MarketStatus::Open);
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: payer.pubkey(),
+            seller_token_ata: user_yes_ata,
+            seller_receive_collateral_ata: user_collateral_ata,
+            order_book,
+            yes_vault,
+            no_vault,
+            market,
+            token_program: spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book),
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send()
+        .unwrap();
+
+    println!("halt/resume market test passed!");
+}
@@ -12,19 +12,19 @@ fn test_loser_cannot_redeem() {
     let base_mint_kp = create_mint(&program, payer);
     let base_mint = base_mint_kp.pubkey();
 
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) =
-        create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
 
     let user_collateral = create_ata(&program, payer, user, base_mint);
     mint_tokens(&program, payer, base_mint, user_collateral, 100);
 
     let (_user_yes, user_no) = mint_pairs_for_user(
         &program,
-        market_kp.pubkey(),
+        market,
         base_mint,
-        yes_mint_kp.pubkey(),
-        no_mint_kp.pubkey(),
-        vault_kp.pubkey(),
+        yes_mint,
+        no_mint,
+        vault,
         market_authority,
         payer,
         user_collateral,
@@ -36,8 +36,8 @@ fn test_loser_cannot_redeem() {
     let loser_pubkey = loser.pubkey();
 
     let loser_collateral = create_ata(&program, payer, loser_pubkey, base_mint);
-    let loser_yes = create_ata(&program, payer, loser_pubkey, yes_mint_kp.pubkey());
-    let loser_no = create_ata(&program, payer, loser_pubkey, no_mint_kp.pubkey());
+    let loser_yes = create_ata(&program, payer, loser_pubkey, yes_mint);
+    let loser_no = create_ata(&program, payer, loser_pubkey, no_mint);
 
     // User transfers all NO tokens to loser
     let transfer_no_to_loser =
@@ -50,33 +50,26 @@ fn test_loser_cannot_redeem() {
         .unwrap();
 
     // Resolve market to YES
-    program
-        .request()
-        .accounts(nfl_blockchain::accounts::ResolveMarket {
-            authority: user,
-            market: market_kp.pubkey(),
-        })
-        .args(nfl_blockchain::instruction::ResolveMarket {
-            outcome: nfl_blockchain::Outcome::Yes,
-        })
-        .send()
-        .unwrap();
+    resolve_market(&program, payer, market, nfl_blockchain::Outcome::Yes).unwrap();
 
     // Loser tries to redeem (should fail)
     let result = program
         .request()
         .accounts(nfl_blockchain::accounts::Redeem {
             user: loser_pubkey,
-            market: market_kp.pubkey(),
+            market,
             base_mint,
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
-            vault: vault_kp.pubkey(),
+            yes_mint,
+            no_mint,
+            vault,
             user_collateral_ata: loser_collateral,
             user_yes_ata: loser_yes,
             user_no_ata: loser_no,
+            recipient_collateral_ata: None,
             market_authority,
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::Redeem {})
         .signer(&loser)
@@ -0,0 +1,414 @@
+use std::{thread, time::Duration};
+
+use anchor_client::solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+use crate::test_utils::*;
+
+fn get_dmm_config_pda(order_book: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"dmm_config", order_book.as_ref()], &nfl_blockchain::id()).0
+}
+
+fn get_market_maker_pda(order_book: Pubkey, maker: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"market_maker", order_book.as_ref(), maker.as_ref()],
+        &nfl_blockchain::id(),
+    )
+    .0
+}
+
+/// Set up the DMM program on `order_book` with the given obligations and benefits.
+fn initialize_dmm_program(
+    program: &anchor_client::Program<&Keypair>,
+    payer: &Keypair,
+    market: Pubkey,
+    order_book: Pubkey,
+    params: nfl_blockchain::DmmProgramParams,
+) {
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::InitializeDmmProgram {
+            authority: payer.pubkey(),
+            market,
+            order_book,
+            dmm_config: get_dmm_config_pda(order_book),
+            system_program: anchor_client::solana_sdk::system_program::id(),
+        })
+        .args(nfl_blockchain::instruction::InitializeDmmProgram { params })
+        .send()
+        .unwrap();
+}
+
+/// Designate `payer` as a market maker; its first sampling period starts now.
+fn register_market_maker(
+    program: &anchor_client::Program<&Keypair>,
+    payer: &Keypair,
+    market: Pubkey,
+    order_book: Pubkey,
+) -> Pubkey {
+    let market_maker = get_market_maker_pda(order_book, payer.pubkey());
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::RegisterMarketMaker {
+            authority: payer.pubkey(),
+            market,
+            order_book,
+            dmm_config: get_dmm_config_pda(order_book),
+            maker: payer.pubkey(),
+            market_maker,
+            system_program: anchor_client::solana_sdk::system_program::id(),
+        })
+        .args(nfl_blockchain::instruction::RegisterMarketMaker {})
+        .send()
+        .unwrap();
+    market_maker
+}
+
+/// Samples are taken on the clock, not when the caller likes: wait for a period to end, then sample.
+fn sample_market_maker(
+    program: &anchor_client::Program<&Keypair>,
+    order_book: Pubkey,
+    market_maker: Pubkey,
+) -> nfl_blockchain::MarketMaker {
+    thread::sleep(Duration::from_secs(2));
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::SampleMarketMaker {
+            order_book,
+            dmm_config: get_dmm_config_pda(order_book),
+            market_maker,
+        })
+        .args(nfl_blockchain::instruction::SampleMarketMaker {})
+        .send()
+        .unwrap();
+    program.account::<nfl_blockchain::MarketMaker>(market_maker).unwrap()
+}
+
+#[test]
+fn test_market_maker_benefits_follow_compliance() {
+    // Setup
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) = initialize_order_book(
+        &program,
+        payer,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+    );
+
+    // Obligations: both sides quoted with size >= 5 within a spread of 10,
+    // every sampling period compliant, periods of one second
+    initialize_dmm_program(
+        &program,
+        payer,
+        market,
+        order_book,
+        nfl_blockchain::DmmProgramParams {
+            max_spread: 10,
+            min_size: 5,
+            min_uptime_bps: 10_000,
+            min_samples: 1,
+            sample_interval_secs: 1,
+            fee_tier: 2,
+            rebate_share_bps: 2_500,
+        },
+    );
+
+    // Quote both outcomes: YES at 0.60 and NO at 0.50, a spread of 60 + 50 - PRICE_SCALE <= 10
+    let user_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral, 100);
+    let (user_yes, user_no) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral,
+        40,
+    );
+    let place_ask = |seller: &Keypair, price: u64, is_yes: bool, token_ata: Pubkey, collateral_ata: Pubkey, market_maker: Option<Pubkey>| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: seller.pubkey(),
+                seller_token_ata: token_ata,
+                seller_receive_collateral_ata: collateral_ata,
+                order_book,
+                yes_vault,
+                no_vault,
+                market,
+                token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault,
+                fee_vault: get_ob_fee_vault_pda(order_book),
+                market_maker,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity: 10, is_yes, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .signer(seller)
+            .send()
+            .unwrap();
+    };
+    let quote = || {
+        place_ask(payer, 60, true, user_yes, user_collateral, None);
+        place_ask(payer, 50, false, user_no, user_collateral, None);
+    };
+    quote();
+
+    // Designate the payer as a market maker
+    let market_maker = register_market_maker(&program, payer, market, order_book);
+    let sample = || sample_market_maker(&program, order_book, market_maker);
+
+    // Quotes resting through the whole period make it compliant and grant the benefits
+    let mm = sample();
+    assert!(mm.samples_total >= 1);
+    assert_eq!(mm.samples_compliant, mm.samples_total);
+    assert!(mm.benefits_active);
+    assert_eq!(mm.fee_tier, 2);
+    assert_eq!(mm.rebate_share_bps, 2_500);
+
+    // Priority: a compliant maker's new order rests ahead of an older one at the same price
+    let other = Keypair::new();
+    fund_account(&program, payer, &other.pubkey(), 1_000_000_000);
+    let other_collateral = create_ata(&program, payer, other.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, other_collateral, 10);
+    let (other_yes, _) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        &other,
+        other_collateral,
+        10,
+    );
+    place_ask(&other, 40, true, other_yes, other_collateral, None);
+    place_ask(payer, 40, true, user_yes, user_collateral, Some(market_maker));
+
+    let (_, orders) = fetch_order_book(&program, order_book);
+    let yes_asks: Vec<_> = orders
+        .iter()
+        .filter(|o| o.is_yes() && o.side() == nfl_blockchain::Side::Ask)
+        .map(|o| (o.owner, o.price, o.has_priority(), o.fee_tier, o.rebate_share_bps))
+        .collect();
+    // The compliant maker's order also keeps its fee tier and rebate share for its fills
+    assert_eq!(
+        yes_asks,
+        vec![
+            (payer.pubkey(), 40, true, 2, 2_500),
+            (other.pubkey(), 40, false, 0, 0),
+            (payer.pubkey(), 60, false, 0, 0),
+        ]
+    );
+
+    // Pulling the quotes and putting them back before the next sample does not count:
+    // the new orders were not resting for the whole period, so benefits are revoked
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::CancelAllOrders {
+            owner: payer.pubkey(),
+            owner_yes_ata: user_yes,
+            owner_no_ata: user_no,
+            owner_collateral_ata: user_collateral,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            collateral_vault,
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelAllOrders {})
+        .send()
+        .unwrap();
+    quote();
+
+    let compliant_before = mm.samples_compliant;
+    let mm = sample();
+    assert!(mm.samples_total > compliant_before);
+    assert_eq!(mm.samples_compliant, compliant_before);
+    assert!(!mm.benefits_active);
+    assert_eq!(mm.fee_tier, 0);
+    assert_eq!(mm.rebate_share_bps, 0);
+
+    println!("market maker compliance test passed!");
+}
+
+#[test]
+fn test_revoked_market_maker_loses_resting_benefits() {
+    // Setup: a 10% taker fee, half of which is rebated to compliant makers
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) =
+        initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::SetFees {
+            authority: payer.pubkey(),
+            market,
+            order_book,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::SetFees { maker_fee_bps: 0, taker_fee_bps: 1_000 })
+        .send()
+        .unwrap();
+    initialize_dmm_program(
+        &program,
+        payer,
+        market,
+        order_book,
+        nfl_blockchain::DmmProgramParams {
+            max_spread: 10,
+            min_size: 5,
+            min_uptime_bps: 10_000,
+            min_samples: 1,
+            sample_interval_secs: 1,
+            fee_tier: 0,
+            rebate_share_bps: 5_000,
+        },
+    );
+
+    // Sellers: the maker quotes both outcomes, another seller offers YES
+    let mint_pairs = |owner: &Keypair, amount: u64| {
+        let collateral = create_ata(&program, payer, owner.pubkey(), base_mint);
+        mint_tokens(&program, payer, base_mint, collateral, amount);
+        let (yes, no) = mint_pairs_for_user(
+            &program,
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            market_authority,
+            owner,
+            collateral,
+            amount,
+        );
+        (collateral, yes, no)
+    };
+    let (maker_collateral, maker_yes, maker_no) = mint_pairs(payer, 200);
+    let other = Keypair::new();
+    fund_account(&program, payer, &other.pubkey(), 1_000_000_000);
+    let (other_collateral, other_yes, _) = mint_pairs(&other, 100);
+
+    let place_ask = |seller: &Keypair, price: u64, quantity: u64, is_yes: bool, token_ata: Pubkey, collateral_ata: Pubkey, market_maker: Option<Pubkey>| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: seller.pubkey(),
+                seller_token_ata: token_ata,
+                seller_receive_collateral_ata: collateral_ata,
+                order_book,
+                yes_vault,
+                no_vault,
+                market,
+                token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault,
+                fee_vault: get_ob_fee_vault_pda(order_book),
+                market_maker,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity, is_yes, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .signer(seller)
+            .send()
+            .unwrap();
+    };
+    place_ask(payer, 60, 10, true, maker_yes, maker_collateral, None);
+    place_ask(payer, 50, 10, false, maker_no, maker_collateral, None);
+
+    // The maker earns its benefits and jumps an older order at the same price
+    let market_maker = register_market_maker(&program, payer, market, order_book);
+    assert!(sample_market_maker(&program, order_book, market_maker).benefits_active);
+    place_ask(&other, 40, 100, true, other_yes, other_collateral, None);
+    place_ask(payer, 40, 100, true, maker_yes, maker_collateral, Some(market_maker));
+
+    let yes_asks = || {
+        let (_, orders) = fetch_order_book(&program, order_book);
+        orders
+            .iter()
+            .filter(|o| o.is_yes() && o.side() == nfl_blockchain::Side::Ask)
+            .map(|o| (o.owner, o.price, o.has_priority(), o.rebate_share_bps))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        yes_asks(),
+        vec![(payer.pubkey(), 40, true, 5_000), (other.pubkey(), 40, false, 0), (payer.pubkey(), 60, false, 0)]
+    );
+
+    // The maker pulls its NO quote, so the next sample revokes its benefits
+    let (_, orders) = fetch_order_book(&program, order_book);
+    let no_ask = orders.iter().find(|o| !o.is_yes() && o.side() == nfl_blockchain::Side::Ask).unwrap().id;
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::CancelOrder {
+            owner: payer.pubkey(),
+            owner_token_ata: maker_no,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            collateral_vault,
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelOrder { order_id: no_ask })
+        .send()
+        .unwrap();
+    assert!(!sample_market_maker(&program, order_book, market_maker).benefits_active);
+
+    // The resting order loses its place in the queue...
+    assert_eq!(
+        yes_asks(),
+        vec![(other.pubkey(), 40, false, 0), (payer.pubkey(), 40, false, 0), (payer.pubkey(), 60, false, 0)]
+    );
+
+    // ...and its rebate: a buyer fills the older order first, and the maker pays the ordinary fee
+    let buyer = Keypair::new();
+    fund_account(&program, payer, &buyer.pubkey(), 1_000_000_000);
+    let buyer_collateral = create_ata(&program, payer, buyer.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, buyer.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 200);
+    let signature = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: buyer.pubkey(),
+            buyer_collateral_ata: buyer_collateral,
+            buyer_receive_token_ata: buyer_yes,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams {
+                quantity: 200,
+                want_yes: true,
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+            },
+        })
+        .accounts(vec![AccountMeta::new(other_collateral, false), AccountMeta::new(maker_collateral, false)])
+        .signer(&buyer)
+        .send()
+        .unwrap();
+
+    let fills = fetch_events::<nfl_blockchain::OrderFilled>(&program, &signature);
+    let fills: Vec<_> = fills.iter().map(|f| (f.maker, f.cost, f.taker_fee, f.maker_fee)).collect();
+    assert_eq!(fills, vec![(other.pubkey(), 40, 4, 0), (payer.pubkey(), 40, 4, 0)]);
+}
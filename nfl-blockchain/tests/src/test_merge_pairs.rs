@@ -0,0 +1,86 @@
+use anchor_client::solana_sdk::signer::Signer;
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_merge_pairs() {
+    // Setup
+    let (program, payer) = setup_client();
+
+    let base_mint_kp = create_mint(&program, payer);
+    let base_mint = base_mint_kp.pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_market(&program, payer, base_mint);
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+
+    let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral_ata,
+        10,
+    );
+
+    // Merge 4 of the 10 pairs back into collateral before resolution
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::MergePairs {
+            user: payer.pubkey(),
+            user_collateral_ata,
+            market,
+            yes_mint,
+            no_mint,
+            vault,
+            user_yes_ata,
+            user_no_ata,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MergePairs { amount: 4 })
+        .send()
+        .unwrap();
+
+    let vault_acc: TokenAccount = program.account(vault).unwrap();
+    let user_collateral: TokenAccount = program.account(user_collateral_ata).unwrap();
+    let user_yes: TokenAccount = program.account(user_yes_ata).unwrap();
+    let user_no: TokenAccount = program.account(user_no_ata).unwrap();
+
+    assert_eq!(vault_acc.amount, 6);
+    assert_eq!(user_collateral.amount, 94);
+    assert_eq!(user_yes.amount, 6);
+    assert_eq!(user_no.amount, 6);
+
+    // Merging more pairs than the user holds must fail
+    let result = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MergePairs {
+            user: payer.pubkey(),
+            user_collateral_ata,
+            market,
+            yes_mint,
+            no_mint,
+            vault,
+            user_yes_ata,
+            user_no_ata,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MergePairs { amount: 7 })
+        .send();
+    assert!(result.is_err(), "Merging more pairs than held should fail");
+
+    println!("merge_pairs test passed!");
+}
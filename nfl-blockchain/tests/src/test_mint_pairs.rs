@@ -11,7 +11,7 @@ fn test_mint_pairs() {
     let base_mint_kp = create_mint(&program, payer);
     let base_mint = base_mint_kp.pubkey();
 
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) =
+    let (market, yes_mint, no_mint, vault, market_authority) =
         create_market(&program, payer, base_mint);
 
     let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
@@ -21,18 +21,18 @@ fn test_mint_pairs() {
     let amount = 10;
     let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
         &program,
-        market_kp.pubkey(),
+        market,
         base_mint,
-        yes_mint_kp.pubkey(),
-        no_mint_kp.pubkey(),
-        vault_kp.pubkey(),
+        yes_mint,
+        no_mint,
+        vault,
         market_authority,
         payer,
         user_collateral_ata,
         amount,
     );
 
-    let vault_account: TokenAccount = program.account(vault_kp.pubkey()).unwrap();
+    let vault_account: TokenAccount = program.account(vault).unwrap();
     let user_yes: TokenAccount = program.account(user_yes_ata).unwrap();
     let user_no: TokenAccount = program.account(user_no_ata).unwrap();
 
@@ -0,0 +1,259 @@
+use anchor_client::solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+const BOND: u64 = 100;
+
+/// A funded wallet holding `BOND` collateral. Returns (wallet, collateral account).
+fn bonded_wallet(
+    program: &anchor_client::Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+) -> (Keypair, Pubkey) {
+    let wallet = Keypair::new();
+    fund_account(program, payer, &wallet.pubkey(), 1_000_000_000);
+    let collateral = create_ata(program, payer, wallet.pubkey(), base_mint);
+    mint_tokens(program, payer, base_mint, collateral, BOND);
+    (wallet, collateral)
+}
+
+/// Claim the bonds of `market`'s settled proposal as `winner`, into `collateral`.
+fn claim_bond(
+    program: &anchor_client::Program<&Keypair>,
+    winner: &Keypair,
+    market: Pubkey,
+    collateral: Pubkey,
+) -> Result<anchor_client::solana_sdk::signature::Signature, anchor_client::ClientError> {
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+    let (market_authority, _) =
+        Pubkey::find_program_address(&[b"market_auth", market.as_ref()], &nfl_blockchain::id());
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::ClaimBond {
+            winner: winner.pubkey(),
+            market,
+            proposal,
+            bond_vault,
+            winner_collateral_ata: collateral,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::ClaimBond {})
+        .signer(winner)
+        .send()
+}
+
+/// Dispute `market`'s proposal as `disputer`, posting the matching bond from `collateral`.
+fn dispute(
+    program: &anchor_client::Program<&Keypair>,
+    disputer: &Keypair,
+    market: Pubkey,
+    collateral: Pubkey,
+) -> Result<anchor_client::solana_sdk::signature::Signature, anchor_client::ClientError> {
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::Dispute {
+            disputer: disputer.pubkey(),
+            market,
+            proposal,
+            bond_vault,
+            disputer_collateral_ata: collateral,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::Dispute {})
+        .signer(disputer)
+        .send()
+}
+
+#[test]
+fn test_undisputed_proposal_finalizes() {
+    // Setup: an oracle proposes with a bond and no challenge period
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (oracle, oracle_collateral) = bonded_wallet(&program, payer, base_mint);
+
+    let resolution = nfl_blockchain::ResolutionParams { bond: BOND, ..test_resolution(oracle.pubkey()) };
+    let (market, ..) = create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, resolution);
+    let (_proposal, bond_vault) = get_resolution_pdas(market);
+
+    propose_resolution(&program, &oracle, market, nfl_blockchain::Outcome::Yes).unwrap();
+    assert_eq!(program.account::<TokenAccount>(bond_vault).unwrap().amount, BOND);
+    assert_eq!(program.account::<TokenAccount>(oracle_collateral).unwrap().amount, 0);
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Proposed);
+    assert!(market_account.outcome == nfl_blockchain::Outcome::Pending);
+
+    // Anyone can finalize; the bond is then the proposer's to claim
+    finalize_resolution(&program, market).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Resolved);
+    assert!(market_account.outcome == nfl_blockchain::Outcome::Yes);
+    assert_eq!(program.account::<TokenAccount>(bond_vault).unwrap().amount, BOND);
+
+    let payer_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    assert_program_error(
+        claim_bond(&program, payer, market, payer_collateral),
+        nfl_blockchain::NflError::NotBondWinner,
+    );
+    claim_bond(&program, &oracle, market, oracle_collateral).unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(oracle_collateral).unwrap().amount, BOND);
+    assert_eq!(program.account::<TokenAccount>(bond_vault).unwrap().amount, 0);
+    assert_program_error(
+        claim_bond(&program, &oracle, market, oracle_collateral),
+        nfl_blockchain::NflError::NoBondToClaim,
+    );
+}
+
+#[test]
+fn test_disputed_proposal_escalates() {
+    // Setup: an hour-long challenge period, with a separate escalation key
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (oracle, oracle_collateral) = bonded_wallet(&program, payer, base_mint);
+    let (challenger, challenger_collateral) = bonded_wallet(&program, payer, base_mint);
+    let escalation = Keypair::new();
+    fund_account(&program, payer, &escalation.pubkey(), 1_000_000_000);
+
+    let resolution = nfl_blockchain::ResolutionParams {
+        escalation_resolver: escalation.pubkey(),
+        challenge_period_secs: 3_600,
+        bond: BOND,
+        ..test_resolution(oracle.pubkey())
+    };
+    let (market, ..) = create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, resolution);
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+
+    // The oracle proposes the wrong outcome; it cannot be finalized during the challenge period
+    propose_resolution(&program, &oracle, market, nfl_blockchain::Outcome::Yes).unwrap();
+    assert!(
+        propose_resolution(&program, &oracle, market, nfl_blockchain::Outcome::No).is_err(),
+        "Only one proposal at a time"
+    );
+    assert_program_error(finalize_resolution(&program, market), nfl_blockchain::NflError::ChallengePeriodActive);
+
+    // A challenger disputes with a matching bond
+    let signature = dispute(&program, &challenger, market, challenger_collateral).unwrap();
+
+    let disputed = fetch_events::<nfl_blockchain::ResolutionDisputed>(&program, &signature);
+    assert_eq!(disputed.len(), 1);
+    assert_eq!((disputed[0].disputer, disputed[0].bond), (challenger.pubkey(), BOND));
+    assert!(disputed[0].proposed_outcome == nfl_blockchain::Outcome::Yes);
+
+    assert_eq!(program.account::<TokenAccount>(bond_vault).unwrap().amount, 2 * BOND);
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Disputed);
+    assert_program_error(finalize_resolution(&program, market), nfl_blockchain::NflError::NoPendingProposal);
+
+    let resolve_dispute = |signer: &Keypair, outcome: nfl_blockchain::Outcome| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::ResolveDispute {
+                escalation_resolver: signer.pubkey(),
+                market,
+                proposal,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::ResolveDispute { outcome })
+            .signer(signer)
+            .send()
+    };
+
+    // The oracle closes its collateral account; that no longer holds up the ruling
+    program
+        .request()
+        .instruction(
+            spl_token::instruction::close_account(
+                &spl_token::id(),
+                &oracle_collateral,
+                &oracle.pubkey(),
+                &oracle.pubkey(),
+                &[],
+            )
+            .unwrap(),
+        )
+        .signer(&oracle)
+        .send()
+        .unwrap();
+
+    // Only the escalation resolver rules; the challenger was right, so they take both bonds
+    assert!(resolve_dispute(&oracle, nfl_blockchain::Outcome::Yes).is_err(), "Resolver cannot rule on a dispute");
+    resolve_dispute(&escalation, nfl_blockchain::Outcome::No).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Resolved);
+    assert!(market_account.outcome == nfl_blockchain::Outcome::No);
+    let proposal_account: nfl_blockchain::ResolutionProposal = program.account(proposal).unwrap();
+    assert_eq!(proposal_account.bond_winner, Some(challenger.pubkey()));
+
+    // The overruled proposer has nothing to claim
+    let oracle_collateral = create_ata(&program, payer, oracle.pubkey(), base_mint);
+    assert_program_error(
+        claim_bond(&program, &oracle, market, oracle_collateral),
+        nfl_blockchain::NflError::NotBondWinner,
+    );
+    let signature = claim_bond(&program, &challenger, market, challenger_collateral).unwrap();
+    assert_eq!(program.account::<TokenAccount>(challenger_collateral).unwrap().amount, 2 * BOND);
+
+    let claimed = fetch_events::<nfl_blockchain::BondClaimed>(&program, &signature);
+    assert_eq!(claimed.len(), 1);
+    assert_eq!((claimed[0].winner, claimed[0].recipient), (challenger.pubkey(), challenger_collateral));
+    assert_eq!(claimed[0].amount, 2 * BOND);
+    assert_eq!(program.account::<TokenAccount>(bond_vault).unwrap().amount, 0);
+}
+
+#[test]
+fn test_challenge_period_bounds() {
+    // Setup: a short challenge period the test can wait out
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (oracle, oracle_collateral) = bonded_wallet(&program, payer, base_mint);
+    let (challenger, challenger_collateral) = bonded_wallet(&program, payer, base_mint);
+
+    let resolution = nfl_blockchain::ResolutionParams {
+        challenge_period_secs: 5,
+        bond: BOND,
+        ..test_resolution(oracle.pubkey())
+    };
+    let (market, ..) = create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, resolution);
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+
+    // Nobody can finalize while the proposal can still be disputed
+    let signature = propose_resolution(&program, &oracle, market, nfl_blockchain::Outcome::Yes).unwrap();
+    assert_program_error(finalize_resolution(&program, market), nfl_blockchain::NflError::ChallengePeriodActive);
+
+    // Once the period is over, a dispute comes too late
+    let proposal_account: nfl_blockchain::ResolutionProposal = program.account(proposal).unwrap();
+    let proposed = fetch_events::<nfl_blockchain::ResolutionProposed>(&program, &signature);
+    assert_eq!(proposed.len(), 1);
+    assert_eq!((proposed[0].proposer, proposed[0].bond), (oracle.pubkey(), BOND));
+    assert_eq!(proposed[0].challenge_ends_ts, proposal_account.challenge_ends_ts);
+    wait_until(&program, proposal_account.challenge_ends_ts);
+    assert_program_error(
+        dispute(&program, &challenger, market, challenger_collateral),
+        nfl_blockchain::NflError::ChallengePeriodOver,
+    );
+    assert_eq!(program.account::<TokenAccount>(challenger_collateral).unwrap().amount, BOND);
+
+    finalize_resolution(&program, market).unwrap();
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Resolved);
+
+    // Only the proposer can take the bond back
+    assert_program_error(
+        claim_bond(&program, &challenger, market, challenger_collateral),
+        nfl_blockchain::NflError::NotBondWinner,
+    );
+    claim_bond(&program, &oracle, market, oracle_collateral).unwrap();
+    assert_eq!(program.account::<TokenAccount>(oracle_collateral).unwrap().amount, BOND);
+    assert_eq!(program.account::<TokenAccount>(bond_vault).unwrap().amount, 0);
+}
@@ -1,36 +1,10 @@
 #![allow(deprecated)]
 
-use anchor_client::solana_sdk::{
-    instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
-};
+use anchor_client::solana_sdk::{instruction::AccountMeta, signature::Keypair, signer::Signer};
 use anchor_spl::token::TokenAccount;
 
 use crate::test_utils::*;
 
-// --- Helpers ---
-fn get_orderbook_pda(market: Pubkey) -> Pubkey {
-    Pubkey::find_program_address(
-        &[b"orderbook", market.as_ref()],
-        &nfl_blockchain::id(),
-    ).0
-}
-
-fn get_ob_vault_pda(order_book: Pubkey, is_yes: bool) -> Pubkey {
-    // Cast to slice to match types
-    let seed: &[u8] = if is_yes { b"yes_vault" } else { b"no_vault" };
-    Pubkey::find_program_address(
-        &[seed, order_book.as_ref()],
-        &nfl_blockchain::id(),
-    ).0
-}
-
-fn fund_account(program: &anchor_client::Program<&Keypair>, payer: &Keypair, to: &Pubkey, amount: u64) {
-    program.request()
-        .instruction(system_instruction::transfer(&payer.pubkey(), to, amount))
-        .send()
-        .unwrap();
-}
-
 // --- Tests ---
 
 #[test]
@@ -38,34 +12,40 @@ fn test_01_init_order_book() {
     // Setup the client and create the required Market and mints before attaching an Order Book
     let (program, payer) = setup_client();
     let base_mint = create_mint(&program, payer).pubkey();
-    let (market_kp, yes_mint_kp, no_mint_kp, _, _) = create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, _, _) = create_market(&program, payer, base_mint);
 
     // Derive the deterministic addresses (PDAs) where the Order Book and its vaults will live
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
+    let order_book_pda = get_orderbook_pda(market);
     let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
     let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
 
     // Send the transaction to initialize the Order Book and create the token vaults on-chain
     program.request()
         .accounts(nfl_blockchain::accounts::InitializeOrderBook {
             authority: payer.pubkey(),
             order_book: order_book_pda,
-            market: market_kp.pubkey(),
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
+            collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
             token_program: anchor_spl::token::spl_token::id(),
             system_program: anchor_client::solana_sdk::system_program::id(),
             rent: anchor_client::solana_sdk::sysvar::rent::id(),
         })
-        .args(nfl_blockchain::instruction::InitializeOrderBook {})
+        .args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 })
         .send()
         .unwrap();
 
     // Fetch the newly created account to verify it exists with the correct default state
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book_pda).unwrap();
+    let (ob_account, orders) = fetch_order_book(&program, order_book_pda);
     assert_eq!(ob_account.capacity, 100);
+    assert_eq!(ob_account.order_count, 0);
+    assert!(orders.is_empty());
 }
 
 #[test]
@@ -73,26 +53,28 @@ fn test_02_place_limit_sell() {
     // Initialize the market and order book infrastructure required for trading
     let (program, payer) = setup_client();
     let base_mint = create_mint(&program, payer).pubkey();
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) = create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
 
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
+    let order_book_pda = get_orderbook_pda(market);
     let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
     let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
 
     program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
-        authority: payer.pubkey(), order_book: order_book_pda, market: market_kp.pubkey(),
-        yes_mint: yes_mint_kp.pubkey(), no_mint: no_mint_kp.pubkey(),
-        yes_vault: yes_vault_pda, no_vault: no_vault_pda,
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
         token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
-    }).args(nfl_blockchain::instruction::InitializeOrderBook {}).send().unwrap();
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
 
     // Fund the user with collateral and mint YES/NO pairs to acquire the specific outcome tokens needed to place a sell order
     let user_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
     mint_tokens(&program, payer, base_mint, user_collateral, 100);
     
     let (user_yes, _) = mint_pairs_for_user(
-        &program, market_kp.pubkey(), base_mint, yes_mint_kp.pubkey(), no_mint_kp.pubkey(),
-        vault_kp.pubkey(), market_authority, payer, user_collateral, 50
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, user_collateral, 50
     );
 
     let price = 50;
@@ -107,17 +89,22 @@ fn test_02_place_limit_sell() {
             order_book: order_book_pda,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
-            market: market_kp.pubkey(),
+            market,
             token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
-        .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity, is_yes: true })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
         .send()
         .unwrap();
 
     // Query the on-chain Order Book account to confirm the order was successfully recorded
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book_pda).unwrap();
-    assert_eq!(ob_account.orders.len(), 1);
-    assert_eq!(ob_account.orders[0].price, 50);
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 1);
+    assert_eq!({ orders[0].price }, 50);
 }
 
 #[test]
@@ -125,18 +112,20 @@ fn test_03_market_buy_full_fill() {
     // Initialize the market and order book infrastructure required for trading
     let (program, payer) = setup_client();
     let base_mint = create_mint(&program, payer).pubkey();
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) = create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
 
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
+    let order_book_pda = get_orderbook_pda(market);
     let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
     let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
 
     program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
-        authority: payer.pubkey(), order_book: order_book_pda, market: market_kp.pubkey(),
-        yes_mint: yes_mint_kp.pubkey(), no_mint: no_mint_kp.pubkey(),
-        yes_vault: yes_vault_pda, no_vault: no_vault_pda,
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
         token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
-    }).args(nfl_blockchain::instruction::InitializeOrderBook {}).send().unwrap();
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
 
     // Create a distinct Seller identity and fund them with collateral to mint the YES tokens they intend to sell
     let seller_kp = Keypair::new();
@@ -146,22 +135,24 @@ fn test_03_market_buy_full_fill() {
     mint_tokens(&program, payer, base_mint, seller_collateral, 100);
     
     // Manually construct the mint transaction to ensure the new Seller keypair signs it correctly
-    let seller_yes = create_ata(&program, payer, seller_kp.pubkey(), yes_mint_kp.pubkey());
-    let seller_no = create_ata(&program, payer, seller_kp.pubkey(), no_mint_kp.pubkey());
+    let seller_yes = create_ata(&program, payer, seller_kp.pubkey(), yes_mint);
+    let seller_no = create_ata(&program, payer, seller_kp.pubkey(), no_mint);
     
     program.request()
         .accounts(nfl_blockchain::accounts::MintPairs {
             user: seller_kp.pubkey(),
             user_collateral_ata: seller_collateral,
-            market: market_kp.pubkey(),
+            market,
             base_mint,
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
-            vault: vault_kp.pubkey(),
+            yes_mint,
+            no_mint,
+            vault,
             user_yes_ata: seller_yes,
             user_no_ata: seller_no,
             market_authority,
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MintPairs { amount: 20 })
         .signer(&seller_kp) // <--- THIS IS THE FIX (Sign as Seller)
@@ -172,16 +163,21 @@ fn test_03_market_buy_full_fill() {
     program.request()
         .accounts(nfl_blockchain::accounts::PlaceLimitSell {
             seller: seller_kp.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
-            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market: market_kp.pubkey(), token_program: anchor_spl::token::spl_token::id(),
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
-        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
         .signer(&seller_kp)
         .send().unwrap();
 
     // Buyer (acting as the default Payer) executes a Market Buy to purchase 10 YES tokens at the best available price 
     let buyer_pubkey = payer.pubkey();
     let buyer_collateral = create_ata(&program, payer, buyer_pubkey, base_mint);
-    let buyer_yes = create_ata(&program, payer, buyer_pubkey, yes_mint_kp.pubkey());
+    let buyer_yes = create_ata(&program, payer, buyer_pubkey, yes_mint);
     mint_tokens(&program, payer, base_mint, buyer_collateral, 1000);
 
     println!("DEBUG: Sending Market Buy transaction...");
@@ -190,14 +186,17 @@ fn test_03_market_buy_full_fill() {
             buyer: buyer_pubkey, 
             buyer_collateral_ata: buyer_collateral,
             buyer_receive_token_ata: buyer_yes,
-            market: market_kp.pubkey(),
+            market,
             order_book: order_book_pda,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MarketBuy {
-            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true }
+            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
         })
         .accounts(vec![ AccountMeta::new(seller_collateral, false) ]) 
         .signer(payer) // Explicit sign for payer just in case
@@ -209,10 +208,10 @@ fn test_03_market_buy_full_fill() {
     assert_eq!(buyer_yes_acc.amount, 10); 
 
     let seller_collateral_acc: TokenAccount = program.account(seller_collateral).unwrap();
-    assert_eq!(seller_collateral_acc.amount, 580); 
+    assert_eq!(seller_collateral_acc.amount, 85); 
 
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book_pda).unwrap();
-    assert_eq!(ob_account.orders.len(), 0);
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 0);
 }
 
 #[test]
@@ -220,34 +219,41 @@ fn test_04_buy_exact_fail_too_expensive() {
     // Initialize the market and order book infrastructure required for trading
     let (program, payer) = setup_client();
     let base_mint = create_mint(&program, payer).pubkey();
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) = create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
 
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
+    let order_book_pda = get_orderbook_pda(market);
     let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
     let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
 
     program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
-        authority: payer.pubkey(), order_book: order_book_pda, market: market_kp.pubkey(),
-        yes_mint: yes_mint_kp.pubkey(), no_mint: no_mint_kp.pubkey(),
-        yes_vault: yes_vault_pda, no_vault: no_vault_pda,
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
         token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
-    }).args(nfl_blockchain::instruction::InitializeOrderBook {}).send().unwrap();
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
 
     // Place a Limit Sell order at a price of 80 to establish liquidity on the book that is intentionally too expensive for the test case
     let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
     mint_tokens(&program, payer, base_mint, seller_collateral, 100);
     
     let (seller_yes, _) = mint_pairs_for_user(
-        &program, market_kp.pubkey(), base_mint, yes_mint_kp.pubkey(), no_mint_kp.pubkey(),
-        vault_kp.pubkey(), market_authority, payer, seller_collateral, 20
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 20
     );
 
     program.request()
         .accounts(nfl_blockchain::accounts::PlaceLimitSell {
             seller: payer.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
-            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market: market_kp.pubkey(), token_program: anchor_spl::token::spl_token::id(),
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
-        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 80, quantity: 10, is_yes: true })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 80, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
         .send().unwrap();
 
     // Since the Buyer and Seller are the same entity in this test, reuse the existing collateral and token accounts to avoid errors
@@ -264,14 +270,17 @@ fn test_04_buy_exact_fail_too_expensive() {
             buyer: buyer_pubkey, 
             buyer_collateral_ata: buyer_collateral, 
             buyer_receive_token_ata: buyer_yes,
-            market: market_kp.pubkey(), 
+            market, 
             order_book: order_book_pda, 
             yes_vault: yes_vault_pda, 
             no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::BuyExact {
-            params: nfl_blockchain::BuyExactParams { quantity: 10, want_yes: true, max_price: 60 }
+            params: nfl_blockchain::BuyExactParams { quantity: 10, want_yes: true, max_price: 60, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
         })
         .accounts(vec![AccountMeta::new(seller_collateral, false)])
         .signer(payer) 
@@ -288,18 +297,20 @@ fn test_05_market_buy_best_price() {
     // Initialize the market ecosystem
     let (program, payer) = setup_client();
     let base_mint = create_mint(&program, payer).pubkey();
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) = create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
 
-    let order_book_pda = get_orderbook_pda(market_kp.pubkey());
+    let order_book_pda = get_orderbook_pda(market);
     let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
     let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
 
     program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
-        authority: payer.pubkey(), order_book: order_book_pda, market: market_kp.pubkey(),
-        yes_mint: yes_mint_kp.pubkey(), no_mint: no_mint_kp.pubkey(),
-        yes_vault: yes_vault_pda, no_vault: no_vault_pda,
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
         token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
-    }).args(nfl_blockchain::instruction::InitializeOrderBook {}).send().unwrap();
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
 
     // --- SELLER SETUP ---
     let seller_kp = Keypair::new();
@@ -309,14 +320,16 @@ fn test_05_market_buy_best_price() {
     mint_tokens(&program, payer, base_mint, seller_collateral, 200); // More collateral needed for fees/rent
     
     // Mint 20 YES/NO pairs to the seller
-    let seller_yes = create_ata(&program, payer, seller_kp.pubkey(), yes_mint_kp.pubkey());
-    let seller_no = create_ata(&program, payer, seller_kp.pubkey(), no_mint_kp.pubkey());
+    let seller_yes = create_ata(&program, payer, seller_kp.pubkey(), yes_mint);
+    let seller_no = create_ata(&program, payer, seller_kp.pubkey(), no_mint);
     
     program.request()
         .accounts(nfl_blockchain::accounts::MintPairs {
-            user: seller_kp.pubkey(), user_collateral_ata: seller_collateral, market: market_kp.pubkey(), base_mint,
-            yes_mint: yes_mint_kp.pubkey(), no_mint: no_mint_kp.pubkey(), vault: vault_kp.pubkey(),
+            user: seller_kp.pubkey(), user_collateral_ata: seller_collateral, market, base_mint,
+            yes_mint, no_mint, vault,
             user_yes_ata: seller_yes, user_no_ata: seller_no, market_authority, token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MintPairs { amount: 20 })
         .signer(&seller_kp) 
@@ -329,9 +342,14 @@ fn test_05_market_buy_best_price() {
     program.request()
         .accounts(nfl_blockchain::accounts::PlaceLimitSell {
             seller: seller_kp.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
-            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market: market_kp.pubkey(), token_program: anchor_spl::token::spl_token::id(),
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
-        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 80, quantity: 10, is_yes: true })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 80, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
         .signer(&seller_kp)
         .send().unwrap();
 
@@ -340,16 +358,21 @@ fn test_05_market_buy_best_price() {
     program.request()
         .accounts(nfl_blockchain::accounts::PlaceLimitSell {
             seller: seller_kp.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
-            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market: market_kp.pubkey(), token_program: anchor_spl::token::spl_token::id(),
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
-        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
         .signer(&seller_kp)
         .send().unwrap();
 
     // --- BUYER EXECUTION ---
     let buyer_pubkey = payer.pubkey();
     let buyer_collateral = create_ata(&program, payer, buyer_pubkey, base_mint);
-    let buyer_yes = create_ata(&program, payer, buyer_pubkey, yes_mint_kp.pubkey());
+    let buyer_yes = create_ata(&program, payer, buyer_pubkey, yes_mint);
     mint_tokens(&program, payer, base_mint, buyer_collateral, 1000);
 
     // Capture Seller Balance BEFORE trade
@@ -363,14 +386,17 @@ fn test_05_market_buy_best_price() {
             buyer: buyer_pubkey, 
             buyer_collateral_ata: buyer_collateral,
             buyer_receive_token_ata: buyer_yes,
-            market: market_kp.pubkey(),
+            market,
             order_book: order_book_pda,
             yes_vault: yes_vault_pda,
             no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
             token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MarketBuy {
-            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true }
+            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
         })
         // We pass the same seller account twice because we might theoretically match multiple orders from same seller
         // but for this test, passing it once or in a list is fine as long as the program finds it.
@@ -387,11 +413,894 @@ fn test_05_market_buy_best_price() {
 
     println!("Seller Profit: {}", profit);
 
-    // If it matched the FIRST order (FIFO), profit would be 800 (10 * 80).
-    // If it matched the BEST PRICE order, profit would be 500 (10 * 50).
-    assert_eq!(profit, 500, "Buyer failed to get the best price! Matched expensive order.");
+    // If it matched the FIRST order (FIFO), profit would be 8 (10 * 0.80).
+    // If it matched the BEST PRICE order, profit would be 5 (10 * 0.50).
+    assert_eq!(profit, 5, "Buyer failed to get the best price! Matched expensive order.");
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 1, "Should have 1 order remaining");
+    assert_eq!({ orders[0].price }, 80, "Remaining order should be the expensive one");
+}
+
+#[test]
+fn test_06_cancel_order_returns_escrow() {
+    // Initialize the market and order book infrastructure required for trading
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 100);
+
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 20
+    );
+
+    program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: payer.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send().unwrap();
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    let order_id = orders[0].id;
+    assert_eq!(program.account::<TokenAccount>(seller_yes).unwrap().amount, 10);
+
+    // A different wallet must not be able to cancel the order
+    let stranger_kp = Keypair::new();
+    fund_account(&program, payer, &stranger_kp.pubkey(), 1_000_000_000);
+    let stranger_yes = create_ata(&program, payer, stranger_kp.pubkey(), yes_mint);
+
+    let result = program.request()
+        .accounts(nfl_blockchain::accounts::CancelOrder {
+            owner: stranger_kp.pubkey(), owner_token_ata: stranger_yes, market,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelOrder { order_id })
+        .signer(&stranger_kp)
+        .send();
+    assert!(result.is_err(), "Only the owner should be able to cancel");
+
+    // The owner cancels and gets the full escrow back
+    program.request()
+        .accounts(nfl_blockchain::accounts::CancelOrder {
+            owner: payer.pubkey(), owner_token_ata: seller_yes, market,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelOrder { order_id })
+        .send().unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(seller_yes).unwrap().amount, 20);
+    assert_eq!(program.account::<TokenAccount>(yes_vault_pda).unwrap().amount, 0);
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 0);
+}
+
+#[test]
+fn test_07_cancel_all_orders() {
+    // Initialize the market and order book infrastructure required for trading
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 100);
+
+    let (seller_yes, seller_no) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 20
+    );
+
+    // Quote both sides of the market: two YES asks and one NO ask
+    for (price, quantity, is_yes, token_ata) in [(40, 10, true, seller_yes), (50, 10, true, seller_yes), (60, 10, false, seller_no)] {
+        program.request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: payer.pubkey(), seller_token_ata: token_ata, seller_receive_collateral_ata: seller_collateral,
+                order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+                fee_vault: get_ob_fee_vault_pda(order_book_pda),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity, is_yes, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .send().unwrap();
+    }
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 3);
+
+    program.request()
+        .accounts(nfl_blockchain::accounts::CancelAllOrders {
+            owner: payer.pubkey(), owner_yes_ata: seller_yes, owner_no_ata: seller_no, owner_collateral_ata: seller_collateral, market,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelAllOrders {})
+        .send().unwrap();
+
+    // Every order is gone and all escrow has been returned
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 0);
+    assert_eq!(program.account::<TokenAccount>(seller_yes).unwrap().amount, 20);
+    assert_eq!(program.account::<TokenAccount>(seller_no).unwrap().amount, 20);
+    assert_eq!(program.account::<TokenAccount>(yes_vault_pda).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(no_vault_pda).unwrap().amount, 0);
+}
+
+#[test]
+fn test_08_limit_buy_filled_by_market_sell_and_sell_exact() {
+    // Initialize the market and order book infrastructure required for trading
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
+
+    // --- BIDDER SETUP ---
+    // The payer rests a bid for 100 YES at 0.40, escrowing 40 collateral
+    let bidder_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    let bidder_yes = create_ata(&program, payer, payer.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, bidder_collateral, 1000);
+
+    program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: payer.pubkey(), buyer_collateral_ata: bidder_collateral, buyer_receive_token_ata: bidder_yes,
+            order_book: order_book_pda, collateral_vault: collateral_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            yes_vault: get_ob_vault_pda(order_book_pda, true),
+            no_vault: get_ob_vault_pda(order_book_pda, false),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: 40, quantity: 100, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send().unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(collateral_vault_pda).unwrap().amount, 40);
+    assert_eq!(program.account::<TokenAccount>(bidder_collateral).unwrap().amount, 960);
+
+    // --- SELLER SETUP ---
+    let seller_kp = Keypair::new();
+    fund_account(&program, payer, &seller_kp.pubkey(), 1_000_000_000);
+
+    let seller_collateral = create_ata(&program, payer, seller_kp.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 200);
+    let seller_yes = create_ata(&program, payer, seller_kp.pubkey(), yes_mint);
+    let seller_no = create_ata(&program, payer, seller_kp.pubkey(), no_mint);
+
+    program.request()
+        .accounts(nfl_blockchain::accounts::MintPairs {
+            user: seller_kp.pubkey(), user_collateral_ata: seller_collateral, market, base_mint,
+            yes_mint, no_mint, vault,
+            user_yes_ata: seller_yes, user_no_ata: seller_no, market_authority, token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MintPairs { amount: 100 })
+        .signer(&seller_kp)
+        .send().unwrap();
+
+    // Seller hits the bid for 60 YES, leaving 40 resting with 16 still escrowed
+    program.request()
+        .accounts(nfl_blockchain::accounts::MarketSellAccounts {
+            seller: seller_kp.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+            market, order_book: order_book_pda, collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketSell {
+            params: nfl_blockchain::MarketSellParams { quantity: 60, sell_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(bidder_yes, false)])
+        .signer(&seller_kp)
+        .send().unwrap();
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 1);
+    assert_eq!({ orders[0].quantity }, 40);
+    assert_eq!({ orders[0].escrow }, 16);
+
+    // Seller sells the rest with a price floor the bid satisfies
+    program.request()
+        .accounts(nfl_blockchain::accounts::MarketSellAccounts {
+            seller: seller_kp.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+            market, order_book: order_book_pda, collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::SellExact {
+            params: nfl_blockchain::SellExactParams { min_price: 40, quantity: 40, sell_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(bidder_yes, false)])
+        .signer(&seller_kp)
+        .send().unwrap();
+
+    // Verify the swap: bidder holds 100 YES, seller received the full 40 escrow, book and vault are empty
+    assert_eq!(program.account::<TokenAccount>(bidder_yes).unwrap().amount, 100);
+    assert_eq!(program.account::<TokenAccount>(seller_yes).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(seller_collateral).unwrap().amount, 140);
+    assert_eq!(program.account::<TokenAccount>(collateral_vault_pda).unwrap().amount, 0);
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 0);
+}
+
+#[test]
+fn test_09_bid_price_protection_and_cancel() {
+    // Initialize the market and order book infrastructure required for trading
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
+
+    let user_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral, 1000);
+    let (user_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, user_collateral, 20
+    );
+
+    // Rest a YES bid at 0.30 (escrow 3)
+    program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: payer.pubkey(), buyer_collateral_ata: user_collateral, buyer_receive_token_ata: user_yes,
+            order_book: order_book_pda, collateral_vault: collateral_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            yes_vault: get_ob_vault_pda(order_book_pda, true),
+            no_vault: get_ob_vault_pda(order_book_pda, false),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: 30, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send().unwrap();
+
+    // An ask at or below the best bid would cross the book and must be rejected
+    let result = program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: payer.pubkey(), seller_token_ata: user_yes, seller_receive_collateral_ata: user_collateral,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 30, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: Some(nfl_blockchain::PostOnly::Reject), self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send();
+    assert!(result.is_err(), "Crossing ask should be rejected");
+
+    // Selling with a floor above the best bid must fail without moving funds
+    let result = program.request()
+        .accounts(nfl_blockchain::accounts::MarketSellAccounts {
+            seller: payer.pubkey(), seller_token_ata: user_yes, seller_receive_collateral_ata: user_collateral,
+            market, order_book: order_book_pda, collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::SellExact {
+            params: nfl_blockchain::SellExactParams { min_price: 40, quantity: 10, sell_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(user_yes, false)])
+        .send();
+    assert!(result.is_err(), "Sell exact below min_price should fail");
+
+    // Cancelling the bid returns the escrowed collateral
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    let order_id = orders[0].id;
+    program.request()
+        .accounts(nfl_blockchain::accounts::CancelOrder {
+            owner: payer.pubkey(), owner_token_ata: user_collateral, market,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::CancelOrder { order_id })
+        .send().unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(user_collateral).unwrap().amount, 980);
+    assert_eq!(program.account::<TokenAccount>(collateral_vault_pda).unwrap().amount, 0);
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 0);
+}
+
+#[test]
+fn test_10_price_time_priority() {
+    // Initialize the market and order book infrastructure required for trading
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+    let (order_book_pda, yes_vault_pda, no_vault_pda, _) = initialize_order_book(
+        &program, payer, market, base_mint, yes_mint, no_mint,
+    );
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 1000);
+
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 80
+    );
+
+    // Asks arrive out of price order: ids 0..=3
+    for price in [50, 30, 50, 40] {
+        program.request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: payer.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+                order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+                fee_vault: get_ob_fee_vault_pda(order_book_pda),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity: 20, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .send().unwrap();
+    }
+
+    // The book keeps them sorted by price, then by time
+    let (ob_account, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(ob_account.order_count, 4);
+    let ranked: Vec<(u64, u64)> = orders.iter().map(|o| (o.id, o.price)).collect();
+    assert_eq!(ranked, vec![(1, 30), (3, 40), (0, 50), (2, 50)]);
+
+    // Buying 50 takes the 30 and 40 asks in full and 10 from the older 50 ask
+    let buyer_kp = Keypair::new();
+    fund_account(&program, payer, &buyer_kp.pubkey(), 1_000_000_000);
+    let buyer_collateral = create_ata(&program, payer, buyer_kp.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, buyer_kp.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 1000);
+
+    program.request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: buyer_kp.pubkey(),
+            buyer_collateral_ata: buyer_collateral,
+            buyer_receive_token_ata: buyer_yes,
+            market,
+            order_book: order_book_pda,
+            yes_vault: yes_vault_pda,
+            no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams { quantity: 50, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(seller_collateral, false); 3])
+        .signer(&buyer_kp)
+        .send().unwrap();
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    let remaining: Vec<(u64, u64)> = orders.iter().map(|o| (o.id, o.quantity)).collect();
+    assert_eq!(remaining, vec![(0, 10), (2, 20)]);
+    assert_eq!(program.account::<TokenAccount>(yes_vault_pda).unwrap().amount, 30);
+}
+
+#[test]
+fn test_11_grow_order_book() {
+    // Start with a book that only has room for two orders
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+        authority: payer.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 2, tick_size: 10, lot_size: 10, min_order_size: 10 }).send().unwrap();
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 100);
+
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 30
+    );
+
+    let place_ask = |price: u64| {
+        program.request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: payer.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+                order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault: get_ob_collateral_vault_pda(order_book_pda),
+                fee_vault: get_ob_fee_vault_pda(order_book_pda),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .send()
+    };
+
+    place_ask(50).unwrap();
+    place_ask(60).unwrap();
+    assert!(place_ask(70).is_err(), "Third order should fail with OrderBookFull");
+
+    // Anyone can pay to add slots
+    let funder = Keypair::new();
+    fund_account(&program, payer, &funder.pubkey(), 1_000_000_000);
+    program.request()
+        .accounts(nfl_blockchain::accounts::GrowOrderBook {
+            payer: funder.pubkey(),
+            market,
+            order_book: order_book_pda,
+            system_program: anchor_client::solana_sdk::system_program::id(),
+        })
+        .args(nfl_blockchain::instruction::GrowOrderBook { additional_capacity: 3 })
+        .signer(&funder)
+        .send().unwrap();
+
+    let data = program.rpc().get_account_data(&order_book_pda).unwrap();
+    assert_eq!(data.len(), nfl_blockchain::OrderBook::space(5));
+
+    // Existing orders survive the resize and new ones fit
+    place_ask(70).unwrap();
+    let (ob_account, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(ob_account.capacity, 5);
+    let prices: Vec<u64> = orders.iter().map(|o| o.price).collect();
+    assert_eq!(prices, vec![50, 60, 70]);
+}
+
+#[test]
+fn test_12_tick_lot_and_min_order_size() {
+    // A book quoting on ticks of 5 in lots of 20, with at least 2 lots per order
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    let init_book = |tick_size: u64| {
+        program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+            authority: payer.pubkey(), order_book: order_book_pda, market,
+            base_mint, yes_mint, no_mint,
+            yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+        }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size, lot_size: 20, min_order_size: 40 }).send()
+    };
+    assert!(init_book(0).is_err(), "Tick size must be positive");
+    // One tick on one lot must be worth whole collateral units: 2 * 20 is not a multiple of PRICE_SCALE
+    assert!(init_book(2).is_err(), "Fills on this grid would need rounding");
+    init_book(5).unwrap();
+
+    let (ob_account, _) = fetch_order_book(&program, order_book_pda);
+    assert_eq!((ob_account.tick_size, ob_account.lot_size, ob_account.min_order_size), (5, 20, 40));
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 100);
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 100
+    );
+
+    let place_ask = |price: u64, quantity: u64| {
+        program.request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: payer.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+                order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault: collateral_vault_pda,
+                fee_vault: get_ob_fee_vault_pda(order_book_pda),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .send()
+    };
+
+    assert!(place_ask(52, 40).is_err(), "Price must be a multiple of the tick size");
+    assert!(place_ask(50, 50).is_err(), "Quantity must be a multiple of the lot size");
+    assert!(place_ask(50, 20).is_err(), "Quantity is below the minimum order size");
+    place_ask(50, 40).unwrap();
+
+    // Takers are held to the same lot and minimum size
+    let result = program.request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: seller_collateral,
+            buyer_receive_token_ata: seller_yes,
+            market,
+            order_book: order_book_pda,
+            yes_vault: yes_vault_pda,
+            no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams { quantity: 30, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .send();
+    assert!(result.is_err(), "Market orders must be whole lots");
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 1);
+    assert_eq!(program.account::<TokenAccount>(yes_vault_pda).unwrap().amount, 40);
+}
+
+#[test]
+fn test_13_price_range() {
+    // Outcome tokens pay at most 1 unit of collateral, so limit prices live in (0, PRICE_SCALE]
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+    let (order_book_pda, yes_vault_pda, no_vault_pda, collateral_vault_pda) = initialize_order_book(
+        &program, payer, market, base_mint, yes_mint, no_mint,
+    );
+
+    let seller_kp = Keypair::new();
+    fund_account(&program, payer, &seller_kp.pubkey(), 1_000_000_000);
+    let seller_collateral = create_ata(&program, payer, seller_kp.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 10);
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, &seller_kp, seller_collateral, 10
+    );
+
+    let place_ask = |price: u64| {
+        program.request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: seller_kp.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+                order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault: collateral_vault_pda,
+                fee_vault: get_ob_fee_vault_pda(order_book_pda),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .signer(&seller_kp)
+            .send()
+    };
+
+    assert!(place_ask(0).is_err(), "A free ask would give tokens away");
+    assert!(place_ask(nfl_blockchain::PRICE_SCALE + 1).is_err(), "No token is worth more than 1");
+
+    // Bids are held to the same range
+    let buyer_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, payer.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 100);
+    let result = program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: payer.pubkey(), buyer_collateral_ata: buyer_collateral, buyer_receive_token_ata: buyer_yes,
+            order_book: order_book_pda, collateral_vault: collateral_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            yes_vault: yes_vault_pda,
+            no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: nfl_blockchain::PRICE_SCALE + 1, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send();
+    assert!(result.is_err(), "Bids above 1 should be rejected");
 
-    let ob_account: nfl_blockchain::OrderBook = program.account(order_book_pda).unwrap();
-    assert_eq!(ob_account.orders.len(), 1, "Should have 1 order remaining");
-    assert_eq!(ob_account.orders[0].price, 80, "Remaining order should be the expensive one");
-}
\ No newline at end of file
+    // An ask at exactly 1 is allowed: a market buy without a price limit then pays 1 per token,
+    // the most the token can ever pay out
+    place_ask(nfl_blockchain::PRICE_SCALE).unwrap();
+    program.request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: buyer_collateral,
+            buyer_receive_token_ata: buyer_yes,
+            market,
+            order_book: order_book_pda,
+            yes_vault: yes_vault_pda,
+            no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            token_program: anchor_spl::token::spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .send().unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(buyer_yes).unwrap().amount, 10);
+    assert_eq!(program.account::<TokenAccount>(buyer_collateral).unwrap().amount, 90);
+    assert_eq!(program.account::<TokenAccount>(seller_collateral).unwrap().amount, 10);
+}
+
+#[test]
+fn test_14_yes_plus_no_never_costs_more_than_a_merge() {
+    // With a NO ask at 0.60, a YES buyer never pays more than 0.40: the pair merges back for only 1
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+    let (order_book_pda, yes_vault_pda, no_vault_pda, collateral_vault_pda) = initialize_order_book(
+        &program, payer, market, base_mint, yes_mint, no_mint,
+    );
+
+    let seller_kp = Keypair::new();
+    fund_account(&program, payer, &seller_kp.pubkey(), 1_000_000_000);
+    let seller_collateral = create_ata(&program, payer, seller_kp.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 10);
+    let (seller_yes, seller_no) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, &seller_kp, seller_collateral, 10
+    );
+
+    for (is_yes, token_ata) in [(true, seller_yes), (false, seller_no)] {
+        program.request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: seller_kp.pubkey(), seller_token_ata: token_ata, seller_receive_collateral_ata: seller_collateral,
+                order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+                collateral_vault: collateral_vault_pda,
+                fee_vault: get_ob_fee_vault_pda(order_book_pda),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell { price: 60, quantity: 10, is_yes, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+            .signer(&seller_kp)
+            .send().unwrap();
+    }
+
+    let buyer_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, payer.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 20);
+    let buy_accounts = || nfl_blockchain::accounts::MarketBuyAccounts {
+        buyer: payer.pubkey(),
+        buyer_collateral_ata: buyer_collateral,
+        buyer_receive_token_ata: buyer_yes,
+        market,
+        order_book: order_book_pda,
+        yes_vault: yes_vault_pda,
+        no_vault: no_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(),
+        event_authority: get_event_authority_pda(),
+        program: nfl_blockchain::id(),
+    };
+    let bid_accounts = || nfl_blockchain::accounts::PlaceLimitBuy {
+        buyer: payer.pubkey(), buyer_collateral_ata: buyer_collateral, buyer_receive_token_ata: buyer_yes,
+        order_book: order_book_pda, collateral_vault: collateral_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+        yes_vault: yes_vault_pda,
+        no_vault: no_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        market_maker: None,
+        event_authority: get_event_authority_pda(),
+        program: nfl_blockchain::id(),
+    };
+
+    // A YES bid at 0.60 would put the pair at 1.20
+    let result = program.request()
+        .accounts(bid_accounts())
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: 60, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .send();
+    assert_program_error(result, nfl_blockchain::NflError::PairPriceAboveScale);
+
+    // Taking the YES ask at 0.60 is capped at 0.40, so an exact buy fails...
+    let result = program.request()
+        .accounts(buy_accounts())
+        .args(nfl_blockchain::instruction::BuyExact {
+            params: nfl_blockchain::BuyExactParams { quantity: 10, want_yes: true, max_price: 60, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .send();
+    assert_program_error(result, nfl_blockchain::NflError::TooExpensive);
+
+    // ...and a market buy fills nothing
+    program.request()
+        .accounts(buy_accounts())
+        .args(nfl_blockchain::instruction::MarketBuy {
+            params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest }
+        })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .send().unwrap();
+    assert_eq!(program.account::<TokenAccount>(buyer_yes).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(buyer_collateral).unwrap().amount, 20);
+    assert_eq!(program.account::<TokenAccount>(seller_collateral).unwrap().amount, 0);
+
+    // A bid at the cap still rests: 0.40 plus the NO ask at 0.60 is exactly 1
+    program.request()
+        .accounts(bid_accounts())
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: 40, quantity: 10, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send().unwrap();
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert_eq!(orders.len(), 3);
+    assert_eq!(program.account::<TokenAccount>(buyer_collateral).unwrap().amount, 16);
+}
+
+#[test]
+fn test_15_order_book_grid_authority() {
+    // Only the market authority can create the book or change its grid
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, _, _) = create_market(&program, payer, base_mint);
+
+    let order_book_pda = get_orderbook_pda(market);
+    let yes_vault_pda = get_ob_vault_pda(order_book_pda, true);
+    let no_vault_pda = get_ob_vault_pda(order_book_pda, false);
+    let collateral_vault_pda = get_ob_collateral_vault_pda(order_book_pda);
+
+    let stranger_kp = Keypair::new();
+    fund_account(&program, payer, &stranger_kp.pubkey(), 1_000_000_000);
+
+    // A stranger cannot claim the market's single book with an unusable grid
+    let result = program.request().accounts(nfl_blockchain::accounts::InitializeOrderBook {
+        authority: stranger_kp.pubkey(), order_book: order_book_pda, market,
+        base_mint, yes_mint, no_mint,
+        yes_vault: yes_vault_pda, no_vault: no_vault_pda, collateral_vault: collateral_vault_pda,
+        fee_vault: get_ob_fee_vault_pda(order_book_pda),
+        token_program: anchor_spl::token::spl_token::id(), system_program: anchor_client::solana_sdk::system_program::id(), rent: anchor_client::solana_sdk::sysvar::rent::id(),
+    }).args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 1, lot_size: u64::MAX - u64::MAX % 100, min_order_size: 0 })
+        .signer(&stranger_kp)
+        .send();
+    assert!(result.is_err(), "Only the market authority can create the book");
+
+    initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+
+    let set_grid = |authority: &Keypair, tick_size: u64, lot_size: u64, min_order_size: u64| {
+        program.request()
+            .accounts(nfl_blockchain::accounts::SetOrderBookGrid {
+                authority: authority.pubkey(),
+                market,
+                order_book: order_book_pda,
+            })
+            .args(nfl_blockchain::instruction::SetOrderBookGrid { tick_size, lot_size, min_order_size })
+            .signer(authority)
+            .send()
+    };
+
+    assert!(set_grid(&stranger_kp, 1, 100, 100).is_err(), "Only the market authority can change the grid");
+    assert!(set_grid(payer, 1, 10, 10).is_err(), "Fills on this grid would need rounding");
+    set_grid(payer, 1, 100, 200).unwrap();
+
+    let (ob_account, _) = fetch_order_book(&program, order_book_pda);
+    assert_eq!((ob_account.tick_size, ob_account.lot_size, ob_account.min_order_size), (1, 100, 200));
+}
+
+#[test]
+fn test_16_remainder_below_minimum_is_dropped() {
+    // A book trading in lots of 10 with at least 40 per order
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) = create_market(&program, payer, base_mint);
+    let (order_book_pda, yes_vault_pda, no_vault_pda, collateral_vault_pda) = initialize_order_book(
+        &program, payer, market, base_mint, yes_mint, no_mint,
+    );
+    program.request()
+        .accounts(nfl_blockchain::accounts::SetOrderBookGrid { authority: payer.pubkey(), market, order_book: order_book_pda })
+        .args(nfl_blockchain::instruction::SetOrderBookGrid { tick_size: 10, lot_size: 10, min_order_size: 40 })
+        .send().unwrap();
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 100);
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program, market, base_mint, yes_mint, no_mint,
+        vault, market_authority, payer, seller_collateral, 100
+    );
+    program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+            seller: payer.pubkey(), seller_token_ata: seller_yes, seller_receive_collateral_ata: seller_collateral,
+            order_book: order_book_pda, yes_vault: yes_vault_pda, no_vault: no_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            collateral_vault: collateral_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitSell { price: 50, quantity: 40, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .send().unwrap();
+
+    // A bid for 60 takes the 40 on offer; the 20 left over is below the minimum and does not rest
+    let buyer_kp = Keypair::new();
+    fund_account(&program, payer, &buyer_kp.pubkey(), 1_000_000_000);
+    let buyer_collateral = create_ata(&program, payer, buyer_kp.pubkey(), base_mint);
+    let buyer_yes = create_ata(&program, payer, buyer_kp.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, buyer_collateral, 100);
+    program.request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: buyer_kp.pubkey(), buyer_collateral_ata: buyer_collateral, buyer_receive_token_ata: buyer_yes,
+            order_book: order_book_pda, collateral_vault: collateral_vault_pda, market, token_program: anchor_spl::token::spl_token::id(),
+            yes_vault: yes_vault_pda,
+            no_vault: no_vault_pda,
+            fee_vault: get_ob_fee_vault_pda(order_book_pda),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy { price: 50, quantity: 60, is_yes: true, time_in_force: nfl_blockchain::TimeInForce::Gtc, post_only: None, self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest })
+        .accounts(vec![AccountMeta::new(seller_collateral, false)])
+        .signer(&buyer_kp)
+        .send().unwrap();
+
+    let (_, orders) = fetch_order_book(&program, order_book_pda);
+    assert!(orders.is_empty());
+    assert_eq!(program.account::<TokenAccount>(buyer_yes).unwrap().amount, 40);
+    assert_eq!(program.account::<TokenAccount>(buyer_collateral).unwrap().amount, 80);
+    assert_eq!(program.account::<TokenAccount>(collateral_vault_pda).unwrap().amount, 0);
+}
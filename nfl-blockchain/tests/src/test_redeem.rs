@@ -1,4 +1,4 @@
-use anchor_client::solana_sdk::signer::Signer;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
 use anchor_spl::token::{spl_token, TokenAccount};
 
 use crate::test_utils::*;
@@ -11,58 +11,51 @@ fn test_redeem_yes() {
     let base_mint_kp = create_mint(&program, payer);
     let base_mint = base_mint_kp.pubkey();
 
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) =
-        create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
 
     let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
     mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
 
     let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
         &program,
-        market_kp.pubkey(),
+        market,
         base_mint,
-        yes_mint_kp.pubkey(),
-        no_mint_kp.pubkey(),
-        vault_kp.pubkey(),
+        yes_mint,
+        no_mint,
+        vault,
         market_authority,
         payer,
         user_collateral_ata,
         10,
     );
 
-    program
-        .request()
-        .accounts(nfl_blockchain::accounts::ResolveMarket {
-            authority: payer.pubkey(),
-            market: market_kp.pubkey(),
-        })
-        .args(nfl_blockchain::instruction::ResolveMarket {
-            outcome: nfl_blockchain::Outcome::Yes,
-        })
-        .send()
-        .unwrap();
+    resolve_market(&program, payer, market, nfl_blockchain::Outcome::Yes).unwrap();
 
     // Redeem YES tokens
     program
         .request()
         .accounts(nfl_blockchain::accounts::Redeem {
             user: payer.pubkey(),
-            market: market_kp.pubkey(),
+            market,
             base_mint,
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
-            vault: vault_kp.pubkey(),
+            yes_mint,
+            no_mint,
+            vault,
             user_collateral_ata,
             user_yes_ata,
             user_no_ata,
+            recipient_collateral_ata: None,
             market_authority,
             token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::Redeem {})
         .send()
         .unwrap();
 
-    let vault_acc: TokenAccount = program.account(vault_kp.pubkey()).unwrap();
+    let vault_acc: TokenAccount = program.account(vault).unwrap();
     let user_collateral: TokenAccount = program.account(user_collateral_ata).unwrap();
     let user_yes: TokenAccount = program.account(user_yes_ata).unwrap();
     let user_no: TokenAccount = program.account(user_no_ata).unwrap();
@@ -74,3 +67,187 @@ fn test_redeem_yes() {
 
     println!("redeem YES test passed!");
 }
+
+/// Resolve a fresh market to `outcome` after a user has minted `pairs` pairs,
+/// redeem both sides, and return (collateral, yes, no, vault) balances afterwards.
+fn mint_resolve_and_redeem(
+    outcome: nfl_blockchain::Outcome,
+    pairs: u64,
+) -> (u64, u64, u64, u64) {
+    let (program, payer) = setup_client();
+
+    let base_mint_kp = create_mint(&program, payer);
+    let base_mint = base_mint_kp.pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+
+    let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral_ata,
+        pairs,
+    );
+
+    resolve_market(&program, payer, market, outcome).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert_eq!(market_account.payout_yes_bps, 5_000);
+    assert_eq!(market_account.payout_no_bps, 5_000);
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::Redeem {
+            user: payer.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            user_collateral_ata,
+            user_yes_ata,
+            user_no_ata,
+            recipient_collateral_ata: None,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::Redeem {})
+        .send()
+        .unwrap();
+
+    (
+        program.account::<TokenAccount>(user_collateral_ata).unwrap().amount,
+        program.account::<TokenAccount>(user_yes_ata).unwrap().amount,
+        program.account::<TokenAccount>(user_no_ata).unwrap().amount,
+        program.account::<TokenAccount>(vault).unwrap().amount,
+    )
+}
+
+#[test]
+fn test_redeem_tie_pays_both_sides() {
+    let (collateral, yes, no, vault) = mint_resolve_and_redeem(nfl_blockchain::Outcome::Tie, 10);
+
+    assert_eq!(collateral, 100); // 10 YES * 0.5 + 10 NO * 0.5 restores the deposit
+    assert_eq!(yes, 0);
+    assert_eq!(no, 0);
+    assert_eq!(vault, 0);
+
+    println!("redeem TIE test passed!");
+}
+
+#[test]
+fn test_redeem_invalid_refunds_collateral() {
+    let (collateral, yes, no, vault) = mint_resolve_and_redeem(nfl_blockchain::Outcome::Invalid, 10);
+
+    assert_eq!(collateral, 100); // voided market releases the vault instead of stranding it
+    assert_eq!(yes, 0);
+    assert_eq!(no, 0);
+    assert_eq!(vault, 0);
+
+    println!("redeem INVALID test passed!");
+}
+
+#[test]
+fn test_redeem_single_pair_on_tie_is_not_rounded_away() {
+    let (collateral, yes, no, vault) = mint_resolve_and_redeem(nfl_blockchain::Outcome::Tie, 1);
+
+    // 1 YES * 0.5 + 1 NO * 0.5 is rounded once, so the pair still returns its unit
+    assert_eq!(collateral, 100);
+    assert_eq!(yes, 0);
+    assert_eq!(no, 0);
+    assert_eq!(vault, 0);
+
+    println!("redeem single pair TIE test passed!");
+}
+
+#[test]
+fn test_redeem_partial_to_recipient() {
+    // Setup
+    let (program, payer) = setup_client();
+
+    let base_mint_kp = create_mint(&program, payer);
+    let base_mint = base_mint_kp.pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+
+    let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral_ata,
+        10,
+    );
+
+    // Settlement account owned by someone else
+    let settlement_owner = Keypair::new();
+    let settlement_ata = create_ata(&program, payer, settlement_owner.pubkey(), base_mint);
+
+    resolve_market(&program, payer, market, nfl_blockchain::Outcome::Yes).unwrap();
+
+    let redeem = |amount: Option<u64>| {
+        let request = program
+            .request()
+            .accounts(nfl_blockchain::accounts::Redeem {
+                user: payer.pubkey(),
+                market,
+                base_mint,
+                yes_mint,
+                no_mint,
+                vault,
+                user_collateral_ata,
+                user_yes_ata,
+                user_no_ata,
+                recipient_collateral_ata: Some(settlement_ata),
+                market_authority,
+                token_program: spl_token::id(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            });
+        match amount {
+            Some(amount) => request.args(nfl_blockchain::instruction::RedeemPartial { amount }),
+            None => request.args(nfl_blockchain::instruction::Redeem {}),
+        }
+        .send()
+    };
+
+    // First tranche of 4 YES
+    redeem(Some(4)).unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(settlement_ata).unwrap().amount, 4);
+    assert_eq!(program.account::<TokenAccount>(user_collateral_ata).unwrap().amount, 90);
+    assert_eq!(program.account::<TokenAccount>(user_yes_ata).unwrap().amount, 6);
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 6);
+
+    // Zero-sized tranches are rejected
+    assert!(redeem(Some(0)).is_err(), "Redeeming zero should fail");
+
+    // Remaining balance
+    redeem(None).unwrap();
+
+    assert_eq!(program.account::<TokenAccount>(settlement_ata).unwrap().amount, 10);
+    assert_eq!(program.account::<TokenAccount>(user_yes_ata).unwrap().amount, 0);
+    assert_eq!(program.account::<TokenAccount>(user_no_ata).unwrap().amount, 10); // loser tokens unchanged
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 0);
+
+    println!("partial redeem to recipient test passed!");
+}
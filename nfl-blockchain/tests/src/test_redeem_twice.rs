@@ -12,52 +12,45 @@ fn test_redeem_twice_should_fail() {
     let base_mint_kp = create_mint(&program, payer);
     let base_mint = base_mint_kp.pubkey();
 
-    let (market_kp, yes_mint_kp, no_mint_kp, vault_kp, market_authority) =
-        create_market(&program, payer, base_mint);
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_expiring_market(&program, payer, base_mint);
 
     let user_collateral_ata = create_ata(&program, payer, user, base_mint);
     mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
 
     let (user_yes_ata, user_no_ata) = mint_pairs_for_user(
         &program,
-        market_kp.pubkey(),
+        market,
         base_mint,
-        yes_mint_kp.pubkey(),
-        no_mint_kp.pubkey(),
-        vault_kp.pubkey(),
+        yes_mint,
+        no_mint,
+        vault,
         market_authority,
         payer,
         user_collateral_ata,
         10,
     );
 
-    program
-        .request()
-        .accounts(nfl_blockchain::accounts::ResolveMarket {
-            authority: user,
-            market: market_kp.pubkey(),
-        })
-        .args(nfl_blockchain::instruction::ResolveMarket {
-            outcome: nfl_blockchain::Outcome::Yes,
-        })
-        .send()
-        .unwrap();
+    resolve_market(&program, payer, market, nfl_blockchain::Outcome::Yes).unwrap();
 
     // First redemption: should succeed
     program
         .request()
         .accounts(nfl_blockchain::accounts::Redeem {
             user,
-            market: market_kp.pubkey(),
+            market,
             base_mint,
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
-            vault: vault_kp.pubkey(),
+            yes_mint,
+            no_mint,
+            vault,
             user_collateral_ata,
             user_yes_ata,
             user_no_ata,
+            recipient_collateral_ata: None,
             market_authority,
             token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::Redeem {})
         .send()
@@ -68,16 +61,19 @@ fn test_redeem_twice_should_fail() {
         .request()
         .accounts(nfl_blockchain::accounts::Redeem {
             user,
-            market: market_kp.pubkey(),
+            market,
             base_mint,
-            yes_mint: yes_mint_kp.pubkey(),
-            no_mint: no_mint_kp.pubkey(),
-            vault: vault_kp.pubkey(),
+            yes_mint,
+            no_mint,
+            vault,
             user_collateral_ata,
             user_yes_ata,
             user_no_ata,
+            recipient_collateral_ata: None,
             market_authority,
             token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::Redeem {})
         .send();
@@ -0,0 +1,74 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_committee_resolution() {
+    // Setup: a 2-of-3 committee settles the market
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let members: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for member in &members {
+        fund_account(&program, payer, &member.pubkey(), 1_000_000_000);
+    }
+
+    let resolution = nfl_blockchain::ResolutionParams {
+        committee: members.iter().map(|member| member.pubkey()).collect(),
+        committee_threshold: 2,
+        ..test_resolution(payer.pubkey())
+    };
+    let (market, ..) = create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, resolution);
+
+    let vote = |member: &Keypair, outcome: nfl_blockchain::Outcome| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::VoteResolution {
+                member: member.pubkey(),
+                market,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::VoteResolution { outcome })
+            .signer(member)
+            .send()
+    };
+
+    // Committee markets cannot be settled by a single key
+    assert!(
+        propose_resolution(&program, payer, market, nfl_blockchain::Outcome::Yes).is_err(),
+        "Committee markets resolve by vote"
+    );
+    let outsider = Keypair::new();
+    fund_account(&program, payer, &outsider.pubkey(), 1_000_000_000);
+    assert!(vote(&outsider, nfl_blockchain::Outcome::Yes).is_err(), "Only members vote");
+
+    // Two conflicting votes do not resolve the market, and repeating a vote is rejected
+    vote(&members[0], nfl_blockchain::Outcome::Yes).unwrap();
+    vote(&members[1], nfl_blockchain::Outcome::No).unwrap();
+    assert!(vote(&members[1], nfl_blockchain::Outcome::No).is_err(), "Same vote twice");
+
+    // A third, different vote leaves the committee split
+    vote(&members[2], nfl_blockchain::Outcome::Invalid).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Open);
+
+    // A member changes their vote and the second YES reaches the threshold
+    vote(&members[2], nfl_blockchain::Outcome::Yes).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Resolved);
+    assert!(market_account.outcome == nfl_blockchain::Outcome::Yes);
+    assert_eq!(
+        market_account.committee_votes,
+        vec![
+            Some(nfl_blockchain::Outcome::Yes),
+            Some(nfl_blockchain::Outcome::No),
+            Some(nfl_blockchain::Outcome::Yes),
+        ]
+    );
+
+    // Votes are locked once the market resolves
+    assert!(vote(&members[1], nfl_blockchain::Outcome::Yes).is_err(), "Market already resolved");
+}
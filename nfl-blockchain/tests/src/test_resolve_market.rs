@@ -1,4 +1,4 @@
-use anchor_client::solana_sdk::signer::Signer;
+use anchor_client::solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 
 use crate::test_utils::*;
 
@@ -10,26 +10,91 @@ fn test_resolve_market_yes() {
     let base_mint_kp = create_mint(&program, payer);
     let base_mint = base_mint_kp.pubkey();
 
-    let (market_kp, _yes_mint_kp, _no_mint_kp, _vault_kp, _market_authority) =
-        create_market(&program, payer, base_mint);
+    let (market, _yes_mint, _no_mint, _vault, _market_authority) =
+        create_expiring_market(&program, payer, base_mint);
 
     // Resolve market to YES
-    program
-        .request()
-        .accounts(nfl_blockchain::accounts::ResolveMarket {
-            authority: payer.pubkey(),
-            market: market_kp.pubkey(),
-        })
-        .args(nfl_blockchain::instruction::ResolveMarket {
-            outcome: nfl_blockchain::Outcome::Yes,
-        })
-        .send()
-        .unwrap();
-
-    let market_account: nfl_blockchain::Market = program.account(market_kp.pubkey()).unwrap();
+    resolve_market(&program, payer, market, nfl_blockchain::Outcome::Yes).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
 
     assert!(market_account.status == nfl_blockchain::MarketStatus::Resolved);
     assert!(market_account.outcome == nfl_blockchain::Outcome::Yes);
 
+    // The outcome is recorded like a finalized proposal, with no bond to claim
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+    let proposal_account: nfl_blockchain::ResolutionProposal = program.account(proposal).unwrap();
+    assert_eq!(proposal_account.bond_winner, Some(payer.pubkey()));
+    assert_eq!(program.account::<anchor_spl::token::TokenAccount>(bond_vault).unwrap().amount, 0);
+
     println!("resolve_market YES test passed!");
 }
+
+#[test]
+fn test_resolve_market_requires_no_challenge_period() {
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    // A market with a challenge period can only be settled through a proposal
+    let resolution = nfl_blockchain::ResolutionParams {
+        challenge_period_secs: 3_600,
+        ..test_resolution(payer.pubkey())
+    };
+    let (market, ..) = create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, resolution);
+    assert_program_error(
+        resolve_market(&program, payer, market, nfl_blockchain::Outcome::Yes),
+        nfl_blockchain::NflError::ChallengePeriodRequired,
+    );
+
+    // Only the resolver may settle a market directly
+    let (market, ..) =
+        create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, test_resolution(payer.pubkey()));
+    let outsider = Keypair::new();
+    fund_account(&program, payer, &outsider.pubkey(), 1_000_000_000);
+    assert!(
+        resolve_market(&program, &outsider, market, nfl_blockchain::Outcome::Yes).is_err(),
+        "Only the resolver may resolve"
+    );
+}
+
+#[test]
+fn test_separate_resolver() {
+    // Setup: the admin creates markets that an oracle key settles
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let oracle = Keypair::new();
+    fund_account(&program, payer, &oracle.pubkey(), 1_000_000_000);
+
+    let set_resolver = |signer: &Keypair, market: Pubkey, resolver: Pubkey| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::SetResolver { authority: signer.pubkey(), market })
+            .args(nfl_blockchain::instruction::SetResolver { resolver })
+            .signer(signer)
+            .send()
+    };
+
+    // Before expiry, only the authority can hand the role to another key
+    let (upcoming, ..) =
+        create_market_with_resolution(&program, payer, base_mint, i64::MAX, test_resolution(payer.pubkey()));
+    assert!(set_resolver(&oracle, upcoming, oracle.pubkey()).is_err(), "Only the authority may set the resolver");
+    set_resolver(payer, upcoming, oracle.pubkey()).unwrap();
+    let market_account: nfl_blockchain::Market = program.account(upcoming).unwrap();
+    assert_eq!(market_account.resolver, oracle.pubkey());
+    assert_eq!(market_account.authority, payer.pubkey());
+
+    // After expiry, the resolver settles the market and the authority cannot
+    let (market, ..) =
+        create_market_with_resolution(&program, payer, base_mint, 1_700_000_000, test_resolution(oracle.pubkey()));
+    assert!(set_resolver(payer, market, payer.pubkey()).is_err(), "Resolver is locked after expiry");
+    assert!(
+        propose_resolution(&program, payer, market, nfl_blockchain::Outcome::No).is_err(),
+        "The authority is not the resolver"
+    );
+    resolve_market(&program, &oracle, market, nfl_blockchain::Outcome::No).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert!(market_account.status == nfl_blockchain::MarketStatus::Resolved);
+    assert!(market_account.outcome == nfl_blockchain::Outcome::No);
+}
@@ -0,0 +1,91 @@
+use anchor_client::solana_sdk::signer::Signer;
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+/// Create a scalar market over [lower, upper], mint 100 LONG/SHORT pairs, resolve it to
+/// `outcome` and redeem both sides. Returns the recorded (long_bps, short_bps) split.
+fn resolve_and_redeem(lower: i64, upper: i64, outcome: nfl_blockchain::Outcome) -> (u16, u16) {
+    let (program, payer) = setup_client();
+
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_scalar_market(&program, payer, base_mint, lower, upper);
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    assert_eq!(market_account.kind, nfl_blockchain::MarketKind::Scalar { lower, upper });
+
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+
+    let (long_ata, short_ata) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral_ata,
+        100,
+    );
+
+    // Binary outcomes do not apply to a scalar market
+    wait_for_expiry(&program, market);
+    assert!(
+        propose_resolution(&program, payer, market, nfl_blockchain::Outcome::Yes).is_err(),
+        "Scalar markets resolve to a value"
+    );
+    resolve_market(&program, payer, market, outcome).unwrap();
+
+    let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+    let split = (market_account.payout_yes_bps, market_account.payout_no_bps);
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::Redeem {
+            user: payer.pubkey(),
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            vault,
+            user_collateral_ata,
+            user_yes_ata: long_ata,
+            user_no_ata: short_ata,
+            recipient_collateral_ata: None,
+            market_authority,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::Redeem {})
+        .send()
+        .unwrap();
+
+    // LONG and SHORT always split one unit of collateral between them
+    assert_eq!(program.account::<TokenAccount>(user_collateral_ata).unwrap().amount, 100);
+    assert_eq!(program.account::<TokenAccount>(vault).unwrap().amount, 0);
+
+    split
+}
+
+#[test]
+fn test_scalar_market_pays_linearly() {
+    // Total points over [30, 60] finishing at 51: LONG earns 70%, SHORT 30%
+    assert_eq!(resolve_and_redeem(30, 60, nfl_blockchain::Outcome::Scalar(51)), (7_000, 3_000));
+}
+
+#[test]
+fn test_scalar_market_clamps_at_bounds() {
+    // Final margin over [-14, 14]: a 21-point win pays LONG in full...
+    assert_eq!(resolve_and_redeem(-14, 14, nfl_blockchain::Outcome::Scalar(21)), (10_000, 0));
+    // ...and a 20-point loss pays SHORT in full
+    assert_eq!(resolve_and_redeem(-14, 14, nfl_blockchain::Outcome::Scalar(-20)), (0, 10_000));
+}
+
+#[test]
+fn test_scalar_market_invalid_refunds() {
+    assert_eq!(resolve_and_redeem(30, 60, nfl_blockchain::Outcome::Invalid), (5_000, 5_000));
+}
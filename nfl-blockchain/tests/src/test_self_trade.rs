@@ -0,0 +1,195 @@
+use anchor_client::solana_sdk::{instruction::AccountMeta, signature::Keypair, signer::Signer};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_post_only_and_self_trade_prevention() {
+    // Setup: the payer quotes both sides of YES, a second maker offers more above
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) =
+        initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+    let fee_vault = get_ob_fee_vault_pda(order_book);
+
+    let user_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral, 1000);
+    let (user_yes, _) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        user_collateral,
+        200,
+    );
+    let balance = |account| program.account::<TokenAccount>(account).unwrap().amount;
+
+    let limit_sell = |seller: &Keypair, token_ata, collateral_ata, price: u64, quantity: u64| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: seller.pubkey(),
+                seller_token_ata: token_ata,
+                seller_receive_collateral_ata: collateral_ata,
+                order_book,
+                yes_vault,
+                no_vault,
+                market,
+                collateral_vault,
+                fee_vault,
+                token_program: spl_token::id(),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell {
+                price,
+                quantity,
+                is_yes: true,
+                time_in_force: nfl_blockchain::TimeInForce::Gtc,
+                post_only: Some(nfl_blockchain::PostOnly::Reject),
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+            })
+            .signer(seller)
+            .send()
+            .unwrap();
+    };
+    let post_only_buy = |price: u64, post_only: nfl_blockchain::PostOnly| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+                buyer: payer.pubkey(),
+                buyer_collateral_ata: user_collateral,
+                buyer_receive_token_ata: user_yes,
+                order_book,
+                collateral_vault,
+                yes_vault,
+                no_vault,
+                fee_vault,
+                market,
+                token_program: spl_token::id(),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitBuy {
+                price,
+                quantity: 100,
+                is_yes: true,
+                time_in_force: nfl_blockchain::TimeInForce::Gtc,
+                post_only: Some(post_only),
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+            })
+            .send()
+    };
+    let market_buy = |quantity: u64, self_trade: nfl_blockchain::SelfTradePrevention, makers: Vec<AccountMeta>| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+                buyer: payer.pubkey(),
+                buyer_collateral_ata: user_collateral,
+                buyer_receive_token_ata: user_yes,
+                market,
+                order_book,
+                yes_vault,
+                no_vault,
+                fee_vault,
+                token_program: spl_token::id(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::MarketBuy {
+                params: nfl_blockchain::MarketBuyParams { quantity, want_yes: true, self_trade },
+            })
+            .accounts(makers)
+            .send()
+    };
+
+    limit_sell(payer, user_yes, user_collateral, 50, 100);
+    assert_eq!(balance(user_yes), 100);
+
+    // Post-only bids never take the ask: rejected, or slid one tick below it
+    assert!(post_only_buy(50, nfl_blockchain::PostOnly::Reject).is_err(), "Order would cross");
+    post_only_buy(60, nfl_blockchain::PostOnly::Slide).unwrap();
+    let (_, orders) = fetch_order_book(&program, order_book);
+    let bid = orders.iter().find(|o| o.side() == nfl_blockchain::Side::Bid).unwrap();
+    assert_eq!(({ bid.price }, { bid.escrow }), (40, 40));
+    assert_eq!(balance(user_collateral), 760);
+
+    // Cancel-newest: the payer's own ask is best, so the buy stops without trading
+    market_buy(50, nfl_blockchain::SelfTradePrevention::CancelNewest, vec![]).unwrap();
+    assert_eq!((balance(user_yes), balance(user_collateral)), (100, 760));
+
+    let result = program
+        .request()
+        .accounts(nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: user_collateral,
+            buyer_receive_token_ata: user_yes,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            fee_vault,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::BuyExact {
+            params: nfl_blockchain::BuyExactParams {
+                max_price: 50,
+                quantity: 50,
+                want_yes: true,
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelNewest,
+            },
+        })
+        .send();
+    assert!(result.is_err(), "An all-or-nothing order fails on a self-trade");
+
+    // Decrement-both: 40 of the ask are returned instead of bought, and no collateral moves
+    market_buy(40, nfl_blockchain::SelfTradePrevention::DecrementBoth, vec![]).unwrap();
+    assert_eq!((balance(user_yes), balance(user_collateral)), (140, 760));
+    let (_, orders) = fetch_order_book(&program, order_book);
+    let ask = orders.iter().find(|o| o.side() == nfl_blockchain::Side::Ask).unwrap();
+    assert_eq!({ ask.quantity }, 60);
+
+    // Cancel-oldest: the payer's ask is cancelled and the buy fills against the next maker
+    let maker = Keypair::new();
+    fund_account(&program, payer, &maker.pubkey(), 1_000_000_000);
+    let maker_collateral = create_ata(&program, payer, maker.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, maker_collateral, 50);
+    let (maker_yes, _) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        &maker,
+        maker_collateral,
+        50,
+    );
+    limit_sell(&maker, maker_yes, maker_collateral, 70, 50);
+
+    market_buy(
+        50,
+        nfl_blockchain::SelfTradePrevention::CancelOldest,
+        vec![AccountMeta::new(maker_collateral, false)],
+    )
+    .unwrap();
+    assert_eq!((balance(user_yes), balance(user_collateral)), (250, 725));
+    assert_eq!(balance(maker_collateral), 35);
+
+    // Only the payer's bid is left
+    let (_, orders) = fetch_order_book(&program, order_book);
+    assert_eq!(orders.len(), 1);
+    assert!(orders[0].side() == nfl_blockchain::Side::Bid);
+}
@@ -0,0 +1,136 @@
+use anchor_client::solana_sdk::{instruction::AccountMeta, signature::Keypair, signer::Signer};
+use anchor_spl::token::{spl_token, TokenAccount};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_time_in_force() {
+    // Setup: a maker bids for 50 YES at 0.20, the payer holds 100 YES to sell
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+
+    let (market, yes_mint, no_mint, vault, market_authority) =
+        create_market(&program, payer, base_mint);
+    let (order_book, yes_vault, no_vault, collateral_vault) =
+        initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+    let fee_vault = get_ob_fee_vault_pda(order_book);
+
+    let maker = Keypair::new();
+    fund_account(&program, payer, &maker.pubkey(), 1_000_000_000);
+    let maker_collateral = create_ata(&program, payer, maker.pubkey(), base_mint);
+    let maker_yes = create_ata(&program, payer, maker.pubkey(), yes_mint);
+    mint_tokens(&program, payer, base_mint, maker_collateral, 100);
+
+    let seller_collateral = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, seller_collateral, 100);
+    let (seller_yes, _) = mint_pairs_for_user(
+        &program,
+        market,
+        base_mint,
+        yes_mint,
+        no_mint,
+        vault,
+        market_authority,
+        payer,
+        seller_collateral,
+        100,
+    );
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::PlaceLimitBuy {
+            buyer: maker.pubkey(),
+            buyer_collateral_ata: maker_collateral,
+            buyer_receive_token_ata: maker_yes,
+            order_book,
+            collateral_vault,
+            yes_vault,
+            no_vault,
+            fee_vault,
+            market,
+            token_program: spl_token::id(),
+            market_maker: None,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PlaceLimitBuy {
+            price: 20,
+            quantity: 50,
+            is_yes: true,
+            time_in_force: nfl_blockchain::TimeInForce::Gtc,
+            post_only: None,
+            self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+        })
+        .signer(&maker)
+        .send()
+        .unwrap();
+
+    let sell = |price: u64, quantity: u64, time_in_force: nfl_blockchain::TimeInForce| {
+        program
+            .request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: payer.pubkey(),
+                seller_token_ata: seller_yes,
+                seller_receive_collateral_ata: seller_collateral,
+                order_book,
+                yes_vault,
+                no_vault,
+                market,
+                collateral_vault,
+                fee_vault,
+                token_program: spl_token::id(),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell {
+                price,
+                quantity,
+                is_yes: true,
+                time_in_force,
+                post_only: None,
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+            })
+            .accounts(vec![AccountMeta::new(maker_yes, false)])
+            .send()
+    };
+
+    let expired = nfl_blockchain::TimeInForce::Gtt { expiry_ts: 1 };
+    assert!(sell(30, 50, expired).is_err(), "Order expiry must be in the future");
+
+    // Fill-or-kill: only 50 of the 100 can be filled, so nothing trades
+    assert!(sell(20, 100, nfl_blockchain::TimeInForce::Fok).is_err(), "Not enough liquidity");
+    assert!(sell(30, 50, nfl_blockchain::TimeInForce::Fok).is_err(), "The bid is below the limit");
+
+    // Immediate-or-cancel: fills the 50 available and drops the rest instead of resting
+    sell(20, 100, nfl_blockchain::TimeInForce::Ioc).unwrap();
+    assert_eq!(program.account::<TokenAccount>(seller_yes).unwrap().amount, 50);
+    assert_eq!(program.account::<TokenAccount>(maker_yes).unwrap().amount, 50);
+    assert_eq!(program.account::<TokenAccount>(seller_collateral).unwrap().amount, 10);
+    let (_, orders) = fetch_order_book(&program, order_book);
+    assert!(orders.is_empty());
+
+    // Good-till-time rests like a GTC order, but carries its expiry
+    sell(30, 50, nfl_blockchain::TimeInForce::Gtt { expiry_ts: i64::MAX }).unwrap();
+    let (_, orders) = fetch_order_book(&program, order_book);
+    assert_eq!(orders.len(), 1);
+    assert_eq!({ orders[0].expiry_ts }, i64::MAX);
+
+    // Only expired orders can be purged
+    let result = program
+        .request()
+        .accounts(nfl_blockchain::accounts::PurgeOrder {
+            owner_token_ata: seller_yes,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            collateral_vault,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::PurgeExpiredOrder { order_id: { orders[0].id } })
+        .send();
+    assert!(result.is_err(), "Order has not expired");
+}
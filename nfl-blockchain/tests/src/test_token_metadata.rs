@@ -0,0 +1,60 @@
+#![allow(deprecated)]
+
+use anchor_client::solana_sdk::{signer::Signer, system_program, sysvar::rent};
+use anchor_spl::metadata::mpl_token_metadata::{self, accounts::Metadata};
+
+use crate::test_utils::*;
+
+#[test]
+fn test_create_outcome_token_metadata() {
+    // Requires the Metaplex Token Metadata program loaded via [[test.genesis]] in Anchor.toml
+    let (program, payer) = setup_client();
+
+    let base_mint = create_mint(&program, payer).pubkey();
+    let (market, yes_mint, no_mint, _vault, market_authority) =
+        create_market(&program, payer, base_mint);
+
+    let yes_token_metadata = Metadata::find_pda(&yes_mint).0;
+    let no_token_metadata = Metadata::find_pda(&no_mint).0;
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::CreateOutcomeTokenMetadata {
+            authority: payer.pubkey(),
+            market,
+            metadata: get_market_metadata_pda(market),
+            yes_mint,
+            no_mint,
+            yes_token_metadata,
+            no_token_metadata,
+            market_authority,
+            token_metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::id(),
+            rent: rent::id(),
+        })
+        .args(nfl_blockchain::instruction::CreateOutcomeTokenMetadata {
+            uri: "https://example.com/markets/2023_01_KC_BUF.json".to_string(),
+        })
+        .send()
+        .unwrap();
+
+    // Metaplex pads names and symbols with NUL bytes
+    let fetch = |address| {
+        let data = program.rpc().get_account_data(&address).unwrap();
+        let metadata = Metadata::safe_deserialize(&data).unwrap();
+        (
+            metadata.name.trim_end_matches('\0').to_string(),
+            metadata.symbol.trim_end_matches('\0').to_string(),
+            metadata.update_authority,
+        )
+    };
+
+    let (name, symbol, update_authority) = fetch(yes_token_metadata);
+    assert_eq!(name, "KC@BUF YES");
+    assert_eq!(symbol, "YES");
+    assert_eq!(update_authority, market_authority);
+
+    let (name, symbol, _) = fetch(no_token_metadata);
+    assert_eq!(name, "KC@BUF NO");
+    assert_eq!(symbol, "NO");
+}
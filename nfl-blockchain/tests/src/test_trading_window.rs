@@ -0,0 +1,143 @@
+use anchor_client::{
+    solana_sdk::{signature::Signature, signer::Signer},
+    ClientError,
+};
+use anchor_spl::token::spl_token;
+
+use crate::test_utils::*;
+
+#[test]
+fn test_trading_window() {
+    let (program, payer) = setup_client();
+    let base_mint = create_mint(&program, payer).pubkey();
+    let user_collateral_ata = create_ata(&program, payer, payer.pubkey(), base_mint);
+    mint_tokens(&program, payer, base_mint, user_collateral_ata, 100);
+
+    // Mint pairs, place a limit sell, market buy and buy exact on a market that only trades
+    // within `window`, and return each result
+    let try_trading = |window: nfl_blockchain::TradingWindow| -> Vec<Result<Signature, ClientError>> {
+        let (market, yes_mint, no_mint, vault, market_authority) =
+            create_market_with_window(&program, payer, base_mint, window);
+        let market_account: nfl_blockchain::Market = program.account(market).unwrap();
+        assert_eq!(market_account.trading_open_ts, window.open_ts);
+        assert_eq!(market_account.trading_close_ts, window.close_ts);
+
+        let (order_book, yes_vault, no_vault, collateral_vault) =
+            initialize_order_book(&program, payer, market, base_mint, yes_mint, no_mint);
+        let fee_vault = get_ob_fee_vault_pda(order_book);
+        let user_yes_ata = create_ata(&program, payer, payer.pubkey(), yes_mint);
+        let user_no_ata = create_ata(&program, payer, payer.pubkey(), no_mint);
+
+        let mint = program
+            .request()
+            .accounts(nfl_blockchain::accounts::MintPairs {
+                user: payer.pubkey(),
+                user_collateral_ata,
+                market,
+                base_mint,
+                yes_mint,
+                no_mint,
+                vault,
+                user_yes_ata,
+                user_no_ata,
+                market_authority,
+                token_program: spl_token::id(),
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::MintPairs { amount: 10 })
+            .send();
+
+        let sell = program
+            .request()
+            .accounts(nfl_blockchain::accounts::PlaceLimitSell {
+                seller: payer.pubkey(),
+                seller_token_ata: user_yes_ata,
+                seller_receive_collateral_ata: user_collateral_ata,
+                order_book,
+                yes_vault,
+                no_vault,
+                market,
+                collateral_vault,
+                fee_vault,
+                token_program: spl_token::id(),
+                market_maker: None,
+                event_authority: get_event_authority_pda(),
+                program: nfl_blockchain::id(),
+            })
+            .args(nfl_blockchain::instruction::PlaceLimitSell {
+                price: 50,
+                quantity: 10,
+                is_yes: true,
+                time_in_force: nfl_blockchain::TimeInForce::Gtc,
+                post_only: None,
+                self_trade: nfl_blockchain::SelfTradePrevention::CancelOldest,
+            })
+            .send();
+
+        let buy_accounts = || nfl_blockchain::accounts::MarketBuyAccounts {
+            buyer: payer.pubkey(),
+            buyer_collateral_ata: user_collateral_ata,
+            buyer_receive_token_ata: user_yes_ata,
+            market,
+            order_book,
+            yes_vault,
+            no_vault,
+            fee_vault,
+            token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        };
+        let self_trade = nfl_blockchain::SelfTradePrevention::CancelOldest;
+        let market_buy = program
+            .request()
+            .accounts(buy_accounts())
+            .args(nfl_blockchain::instruction::MarketBuy {
+                params: nfl_blockchain::MarketBuyParams { quantity: 10, want_yes: true, self_trade },
+            })
+            .send();
+        let buy_exact = program
+            .request()
+            .accounts(buy_accounts())
+            .args(nfl_blockchain::instruction::BuyExact {
+                params: nfl_blockchain::BuyExactParams { quantity: 10, want_yes: true, max_price: 60, self_trade },
+            })
+            .send();
+
+        vec![mint, sell, market_buy, buy_exact]
+    };
+
+    // A pre-game market whose kickoff has passed is closed without being halted
+    let closed = nfl_blockchain::TradingWindow { open_ts: 0, close_ts: 1_700_000_000 };
+    for result in try_trading(closed) {
+        assert_program_error(result, nfl_blockchain::NflError::TradingClosed);
+    }
+
+    // A market whose window has not opened yet
+    let upcoming = nfl_blockchain::TradingWindow { open_ts: i64::MAX - 1, close_ts: i64::MAX };
+    for result in try_trading(upcoming) {
+        assert_program_error(result, nfl_blockchain::NflError::TradingNotStarted);
+    }
+
+    // Inside the window pairs can be minted and offered
+    let mut open = try_trading(ALWAYS_OPEN).into_iter();
+    open.next().unwrap().unwrap();
+    open.next().unwrap().unwrap();
+
+    // Trading may not outlast the market's expiry
+    let event_id = unique_event_id();
+    let metadata = nfl_blockchain::MarketMetadataParams { event_id: event_id.clone(), ..sample_metadata() };
+    let result = create_market_with(
+        &program,
+        payer,
+        base_mint,
+        &event_id,
+        nfl_blockchain::instruction::CreateMarket {
+            metadata,
+            expiry_ts: 1_700_000_000,
+            trading_window: nfl_blockchain::TradingWindow { open_ts: 0, close_ts: 1_700_000_001 },
+            resolution: test_resolution(payer.pubkey()),
+        },
+    );
+    assert_program_error(result, nfl_blockchain::NflError::InvalidTradingWindow);
+}
@@ -4,15 +4,20 @@ use std::str::FromStr;
 use anchor_client::{
     solana_sdk::{
         commitment_config::CommitmentConfig,
+        instruction::InstructionError,
         pubkey::Pubkey,
-        signature::{read_keypair_file, Keypair},
+        signature::{read_keypair_file, Keypair, Signature},
         signer::Signer,
         system_instruction, system_program,
-        sysvar::rent,
+        sysvar::{clock, rent},
+        transaction::TransactionError,
     },
-    Client, Cluster, Program,
+    anchor_lang::{event::EVENT_IX_TAG_LE, Event, InstructionData},
+    Client, ClientError, Cluster, Program,
 };
 use anchor_spl::token::{spl_token, Mint};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_transaction_status_client_types::{UiInnerInstructions, UiInstruction, UiTransactionEncoding};
 
 /// Load payer from ANCHOR_WALLET and connect to localnet.
 pub fn setup_client() -> (Program<&'static Keypair>, &'static Keypair) {
@@ -29,6 +34,46 @@ pub fn setup_client() -> (Program<&'static Keypair>, &'static Keypair) {
     (program, payer)
 }
 
+/// Derive the OrderBook PDA for `market`.
+pub fn get_orderbook_pda(market: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"orderbook", market.as_ref()], &nfl_blockchain::id()).0
+}
+
+/// Fetch an order book's header and live orders.
+/// The book is a zero-copy account with trailing order slots, so it is decoded from raw data.
+pub fn fetch_order_book(
+    program: &Program<&Keypair>,
+    order_book: Pubkey,
+) -> (nfl_blockchain::OrderBook, Vec<nfl_blockchain::Order>) {
+    let data = program.rpc().get_account_data(&order_book).unwrap();
+    nfl_blockchain::OrderBook::unpack(&data).unwrap()
+}
+
+/// Derive the YES or NO escrow vault PDA for `order_book`.
+pub fn get_ob_vault_pda(order_book: Pubkey, is_yes: bool) -> Pubkey {
+    let seed: &[u8] = if is_yes { b"yes_vault" } else { b"no_vault" };
+    Pubkey::find_program_address(&[seed, order_book.as_ref()], &nfl_blockchain::id()).0
+}
+
+/// Derive the bid collateral escrow vault PDA for `order_book`.
+pub fn get_ob_collateral_vault_pda(order_book: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"collateral_vault", order_book.as_ref()], &nfl_blockchain::id()).0
+}
+
+/// Derive the trading fee vault PDA for `order_book`.
+pub fn get_ob_fee_vault_pda(order_book: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_vault", order_book.as_ref()], &nfl_blockchain::id()).0
+}
+
+/// Transfer `amount` lamports from `payer` to `to`.
+pub fn fund_account(program: &Program<&Keypair>, payer: &Keypair, to: &Pubkey, amount: u64) {
+    program
+        .request()
+        .instruction(system_instruction::transfer(&payer.pubkey(), to, amount))
+        .send()
+        .unwrap();
+}
+
 /// Create & initialize a mint with 6 decimals owned by `mint_authority`.
 pub fn create_mint(program: &Program<&Keypair>, mint_authority: &Keypair) -> Keypair {
     let mint = Keypair::new();
@@ -123,46 +168,435 @@ pub fn mint_tokens(
     program.request().instruction(ix).send().unwrap();
 }
 
-/// Create a market.
+/// Derive the MarketMetadata PDA for `market`.
+pub fn get_market_metadata_pda(market: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"market_metadata", market.as_ref()], &nfl_blockchain::id()).0
+}
+
+/// Derive the PDA the program signs its self-CPI events with, passed as `event_authority`.
+pub fn get_event_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &nfl_blockchain::id()).0
+}
+
+/// Decode the `E` events the program emitted through self-CPI in transaction `signature`.
+pub fn fetch_events<E: Event>(program: &Program<&Keypair>, signature: &Signature) -> Vec<E> {
+    // Transactions are sent at `processed`, but can only be fetched once confirmed
+    let transaction = (0..20)
+        .find_map(|_| {
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            };
+            let transaction = program.rpc().get_transaction_with_config(signature, config).ok();
+            if transaction.is_none() {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            }
+            transaction
+        })
+        .expect("Transaction not found");
+
+    let inner_instructions: Option<Vec<UiInnerInstructions>> =
+        transaction.transaction.meta.unwrap().inner_instructions.into();
+    inner_instructions
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|inner| inner.instructions)
+        .filter_map(|instruction| match instruction {
+            UiInstruction::Compiled(instruction) => bs58::decode(instruction.data).into_vec().ok(),
+            UiInstruction::Parsed(_) => None,
+        })
+        .filter_map(|data| {
+            let event = data.strip_prefix(EVENT_IX_TAG_LE)?.strip_prefix(E::DISCRIMINATOR)?;
+            E::try_from_slice(event).ok()
+        })
+        .collect()
+}
+
+/// Assert that a transaction failed with the program error `expected`.
+pub fn assert_program_error<T: std::fmt::Debug>(result: Result<T, ClientError>, expected: nfl_blockchain::NflError) {
+    let expected_name = format!("{:?}", expected);
+    let expected_code: u32 = expected.into();
+    let code = match result.expect_err(&format!("Expected {}", expected_name)) {
+        ClientError::SolanaClientError(err) => match err.get_transaction_error() {
+            Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) => code,
+            other => panic!("Expected {}, got {:?}", expected_name, other),
+        },
+        other => panic!("Expected {}, got {}", expected_name, other),
+    };
+    assert_eq!(code, expected_code, "Expected {}", expected_name);
+}
+
+/// Metadata used for markets created by the test helpers.
+pub fn sample_metadata() -> nfl_blockchain::MarketMetadataParams {
+    nfl_blockchain::MarketMetadataParams {
+        title: "KC @ BUF: Bills win".to_string(),
+        resolution_criteria: "Resolves YES if BUF wins, including overtime.".to_string(),
+        resolution_criteria_hash: [0; 32],
+        category: "moneyline".to_string(),
+        event_id: "2023_01_KC_BUF_ML".to_string(),
+        home_team: "BUF".to_string(),
+        away_team: "KC".to_string(),
+        kickoff_ts: 1_700_000_000,
+    }
+}
+
+/// Addresses of a market and its order book, all derived from (authority, event_id).
+pub struct MarketAddresses {
+    pub market: Pubkey,
+    pub yes_mint: Pubkey,
+    pub no_mint: Pubkey,
+    pub vault: Pubkey,
+    pub market_authority: Pubkey,
+    pub metadata: Pubkey,
+    pub order_book: Pubkey,
+    pub yes_vault: Pubkey,
+    pub no_vault: Pubkey,
+    pub collateral_vault: Pubkey,
+    pub fee_vault: Pubkey,
+}
+
+impl MarketAddresses {
+    pub fn derive(authority: Pubkey, event_id: &str) -> Self {
+        let program_id = nfl_blockchain::id();
+        let market =
+            Pubkey::find_program_address(&[b"market", authority.as_ref(), event_id.as_bytes()], &program_id).0;
+        let market_pda = |seed: &[u8]| Pubkey::find_program_address(&[seed, market.as_ref()], &program_id).0;
+        let order_book = get_orderbook_pda(market);
+
+        MarketAddresses {
+            market,
+            yes_mint: market_pda(b"yes_mint"),
+            no_mint: market_pda(b"no_mint"),
+            vault: market_pda(b"vault"),
+            market_authority: market_pda(b"market_auth"),
+            metadata: get_market_metadata_pda(market),
+            order_book,
+            yes_vault: get_ob_vault_pda(order_book, true),
+            no_vault: get_ob_vault_pda(order_book, false),
+            collateral_vault: get_ob_collateral_vault_pda(order_book),
+            fee_vault: get_ob_fee_vault_pda(order_book),
+        }
+    }
+}
+
+/// Derive the demo market's addresses from the AUTHORITY and EVENT_ID environment variables.
+pub fn market_addresses_from_env() -> MarketAddresses {
+    let env = |name: &str| {
+        std::env::var(name).unwrap_or_else(|_| {
+            eprintln!("Error: {} environment variable is not set.", name);
+            eprintln!("Please run demo1 first and export the market information.");
+            std::process::exit(1);
+        })
+    };
+    let authority = env("AUTHORITY").parse().unwrap_or_else(|_| {
+        eprintln!("Error: Invalid AUTHORITY address.");
+        std::process::exit(1);
+    });
+    MarketAddresses::derive(authority, &env("EVENT_ID"))
+}
+
+/// A fresh event id, so each test market gets its own addresses.
+pub fn unique_event_id() -> String {
+    Keypair::new().pubkey().to_string()[..32].to_string()
+}
+
+/// Create a market for a fresh event that never expires, so it cannot be resolved.
+/// Returns (market, yes_mint, no_mint, vault, market_authority).
 pub fn create_market(
     program: &Program<&Keypair>,
     payer: &Keypair,
     base_mint: Pubkey,
-) -> (Keypair, Keypair, Keypair, Keypair, Pubkey) {
-    let market = Keypair::new();
-    let yes_mint = Keypair::new();
-    let no_mint = Keypair::new();
-    let vault = Keypair::new();
+) -> (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) {
+    create_market_for_event(program, payer, base_mint, &unique_event_id(), i64::MAX).unwrap()
+}
+
+/// Create a market for `event_id` that trades until `expiry_ts`; fails if `payer` already has a
+/// market for that event. With an `expiry_ts` of `i64::MAX` the market trades for as long as the
+/// test needs but can never be resolved.
+pub fn create_market_for_event(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    event_id: &str,
+    expiry_ts: i64,
+) -> Result<(Pubkey, Pubkey, Pubkey, Pubkey, Pubkey), ClientError> {
+    let metadata = nfl_blockchain::MarketMetadataParams { event_id: event_id.to_string(), ..sample_metadata() };
+    create_market_with(
+        program,
+        payer,
+        base_mint,
+        event_id,
+        nfl_blockchain::instruction::CreateMarket {
+            metadata,
+            expiry_ts,
+            trading_window: open_until(expiry_ts),
+            resolution: test_resolution(payer.pubkey()),
+        },
+    )
+}
+
+/// Trading window for test markets that never expire: open from the epoch and never closing.
+pub const ALWAYS_OPEN: nfl_blockchain::TradingWindow = nfl_blockchain::TradingWindow { open_ts: 0, close_ts: i64::MAX };
+
+/// Trading window open from the epoch until `expiry_ts`, the latest a market's trading may close.
+pub fn open_until(expiry_ts: i64) -> nfl_blockchain::TradingWindow {
+    nfl_blockchain::TradingWindow { open_ts: 0, close_ts: expiry_ts }
+}
+
+/// Seconds a market from `create_expiring_market` trades before it expires. A test must finish
+/// minting and trading within this time; `resolve_market` then waits for the expiry.
+pub const TEST_MARKET_LIFETIME_SECS: i64 = 20;
+
+/// Unix timestamp of the local validator's clock.
+pub fn validator_now(program: &Program<&Keypair>) -> i64 {
+    let account = program.rpc().get_account(&clock::id()).unwrap();
+    anchor_client::solana_sdk::account::from_account::<clock::Clock, _>(&account).unwrap().unix_timestamp
+}
+
+/// Block until the validator's clock reaches `ts`.
+pub fn wait_until(program: &Program<&Keypair>, ts: i64) {
+    while validator_now(program) < ts {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+}
+
+/// Block until `market` has expired on the validator's clock, so it can be resolved.
+pub fn wait_for_expiry(program: &Program<&Keypair>, market: Pubkey) {
+    wait_until(program, program.account::<nfl_blockchain::Market>(market).unwrap().expiry_ts);
+}
+
+/// Create a market for a fresh event that trades for `TEST_MARKET_LIFETIME_SECS` and then
+/// expires, for tests that resolve it.
+pub fn create_expiring_market(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+) -> (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let expiry_ts = validator_now(program) + TEST_MARKET_LIFETIME_SECS;
+    create_market_with_resolution(program, payer, base_mint, expiry_ts, test_resolution(payer.pubkey()))
+}
+
+/// Create a binary market for a fresh event that can only be traded within `trading_window`.
+/// The market expires when trading closes.
+pub fn create_market_with_window(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    trading_window: nfl_blockchain::TradingWindow,
+) -> (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let event_id = unique_event_id();
+    let metadata = nfl_blockchain::MarketMetadataParams { event_id: event_id.clone(), ..sample_metadata() };
+    create_market_with(
+        program,
+        payer,
+        base_mint,
+        &event_id,
+        nfl_blockchain::instruction::CreateMarket {
+            metadata,
+            expiry_ts: trading_window.close_ts,
+            trading_window,
+            resolution: test_resolution(payer.pubkey()),
+        },
+    )
+    .unwrap()
+}
+
+/// Resolution settings for test markets: `resolver` proposes and rules on disputes,
+/// with no challenge period or bond, so a proposal can be finalized immediately.
+pub fn test_resolution(resolver: Pubkey) -> nfl_blockchain::ResolutionParams {
+    nfl_blockchain::ResolutionParams {
+        resolver,
+        escalation_resolver: resolver,
+        challenge_period_secs: 0,
+        bond: 0,
+        committee: vec![],
+        committee_threshold: 0,
+    }
+}
+
+/// Create a binary market for a fresh event with the given expiry and resolution settings.
+/// It trades until expiry, so a market that has already expired cannot be traded.
+pub fn create_market_with_resolution(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    expiry_ts: i64,
+    resolution: nfl_blockchain::ResolutionParams,
+) -> (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let event_id = unique_event_id();
+    let metadata = nfl_blockchain::MarketMetadataParams { event_id: event_id.clone(), ..sample_metadata() };
+    create_market_with(
+        program,
+        payer,
+        base_mint,
+        &event_id,
+        nfl_blockchain::instruction::CreateMarket { metadata, expiry_ts, trading_window: open_until(expiry_ts), resolution },
+    )
+    .unwrap()
+}
+
+/// Create a scalar market over [lower, upper] for a fresh event that trades for
+/// `TEST_MARKET_LIFETIME_SECS` and then expires.
+pub fn create_scalar_market(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    lower: i64,
+    upper: i64,
+) -> (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let event_id = unique_event_id();
+    let metadata = nfl_blockchain::MarketMetadataParams { event_id: event_id.clone(), ..sample_metadata() };
+    let expiry_ts = validator_now(program) + TEST_MARKET_LIFETIME_SECS;
+    create_market_with(
+        program,
+        payer,
+        base_mint,
+        &event_id,
+        nfl_blockchain::instruction::CreateScalarMarket {
+            metadata,
+            expiry_ts,
+            trading_window: open_until(expiry_ts),
+            resolution: test_resolution(payer.pubkey()),
+            lower,
+            upper,
+        },
+    )
+    .unwrap()
+}
 
-    let (market_authority, _bump) =
-        Pubkey::find_program_address(&[b"market_auth", market.pubkey().as_ref()], &program.id());
+/// Create a binary or scalar market from `args` at the addresses derived from `event_id`.
+pub fn create_market_with(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    base_mint: Pubkey,
+    event_id: &str,
+    args: impl InstructionData,
+) -> Result<(Pubkey, Pubkey, Pubkey, Pubkey, Pubkey), ClientError> {
+    let addresses = MarketAddresses::derive(payer.pubkey(), event_id);
 
     let system_program_pk = Pubkey::new_from_array(system_program::id().to_bytes());
-    let expiry_ts = 1_700_000_000i64;
 
     program
         .request()
         .accounts(nfl_blockchain::accounts::CreateMarket {
             authority: payer.pubkey(),
-            market: market.pubkey(),
+            market: addresses.market,
             base_mint,
-            yes_mint: yes_mint.pubkey(),
-            no_mint: no_mint.pubkey(),
-            vault: vault.pubkey(),
-            market_authority,
+            yes_mint: addresses.yes_mint,
+            no_mint: addresses.no_mint,
+            vault: addresses.vault,
+            market_authority: addresses.market_authority,
+            metadata: addresses.metadata,
             token_program: spl_token::id(),
             system_program: system_program_pk,
             rent: rent::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
-        .args(nfl_blockchain::instruction::CreateMarket { expiry_ts })
-        .signer(&market)
-        .signer(&yes_mint)
-        .signer(&no_mint)
-        .signer(&vault)
+        .args(args)
+        .send()?;
+
+    Ok((
+        addresses.market,
+        addresses.yes_mint,
+        addresses.no_mint,
+        addresses.vault,
+        addresses.market_authority,
+    ))
+}
+
+/// Derive the resolution proposal and bond vault PDAs of `market`.
+pub fn get_resolution_pdas(market: Pubkey) -> (Pubkey, Pubkey) {
+    let program_id = nfl_blockchain::id();
+    (
+        Pubkey::find_program_address(&[b"resolution_proposal", market.as_ref()], &program_id).0,
+        Pubkey::find_program_address(&[b"bond_vault", market.as_ref()], &program_id).0,
+    )
+}
+
+/// Propose `outcome` for `market` as `resolver`, posting the bond from their collateral account.
+pub fn propose_resolution(
+    program: &Program<&Keypair>,
+    resolver: &Keypair,
+    market: Pubkey,
+    outcome: nfl_blockchain::Outcome,
+) -> Result<(), ClientError> {
+    let base_mint = program.account::<nfl_blockchain::Market>(market)?.base_mint;
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+    let (market_authority, _) =
+        Pubkey::find_program_address(&[b"market_auth", market.as_ref()], &nfl_blockchain::id());
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::ProposeResolution {
+            resolver: resolver.pubkey(),
+            market,
+            base_mint,
+            proposal,
+            bond_vault,
+            resolver_collateral_ata: create_ata(program, resolver, resolver.pubkey(), base_mint),
+            market_authority,
+            token_program: spl_token::id(),
+            system_program: Pubkey::new_from_array(system_program::id().to_bytes()),
+            rent: rent::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::ProposeResolution { outcome })
+        .signer(resolver)
+        .send()?;
+    Ok(())
+}
+
+/// Finalize the undisputed proposal of `market`; the proposer can then claim its bond.
+pub fn finalize_resolution(program: &Program<&Keypair>, market: Pubkey) -> Result<Signature, ClientError> {
+    let (proposal, _bond_vault) = get_resolution_pdas(market);
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::FinalizeResolution {
+            market,
+            proposal,
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::FinalizeResolution {})
         .send()
-        .unwrap();
+}
+
+/// Resolve a market with no challenge period through `resolve_market` once it has expired.
+pub fn resolve_market(
+    program: &Program<&Keypair>,
+    resolver: &Keypair,
+    market: Pubkey,
+    outcome: nfl_blockchain::Outcome,
+) -> Result<Signature, ClientError> {
+    wait_for_expiry(program, market);
+
+    let base_mint = program.account::<nfl_blockchain::Market>(market)?.base_mint;
+    let (proposal, bond_vault) = get_resolution_pdas(market);
+    let (market_authority, _) =
+        Pubkey::find_program_address(&[b"market_auth", market.as_ref()], &nfl_blockchain::id());
 
-    (market, yes_mint, no_mint, vault, market_authority)
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::ResolveMarket {
+            resolver: resolver.pubkey(),
+            market,
+            base_mint,
+            proposal,
+            bond_vault,
+            market_authority,
+            token_program: spl_token::id(),
+            system_program: Pubkey::new_from_array(system_program::id().to_bytes()),
+            rent: rent::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
+        })
+        .args(nfl_blockchain::instruction::ResolveMarket { outcome })
+        .signer(resolver)
+        .send()
 }
 
 /// Mint YES/NO pairs for `user`.
@@ -195,6 +629,8 @@ pub fn mint_pairs_for_user(
             user_no_ata,
             market_authority,
             token_program: spl_token::id(),
+            event_authority: get_event_authority_pda(),
+            program: nfl_blockchain::id(),
         })
         .args(nfl_blockchain::instruction::MintPairs { amount })
         .signer(user)
@@ -203,3 +639,42 @@ pub fn mint_pairs_for_user(
 
     (user_yes_ata, user_no_ata)
 }
+
+/// Initialize the order book for `market`.
+/// Returns (order_book, yes_vault, no_vault, collateral_vault).
+pub fn initialize_order_book(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    market: Pubkey,
+    base_mint: Pubkey,
+    yes_mint: Pubkey,
+    no_mint: Pubkey,
+) -> (Pubkey, Pubkey, Pubkey, Pubkey) {
+    let order_book = get_orderbook_pda(market);
+    let yes_vault = get_ob_vault_pda(order_book, true);
+    let no_vault = get_ob_vault_pda(order_book, false);
+    let collateral_vault = get_ob_collateral_vault_pda(order_book);
+
+    program
+        .request()
+        .accounts(nfl_blockchain::accounts::InitializeOrderBook {
+            authority: payer.pubkey(),
+            order_book,
+            market,
+            base_mint,
+            yes_mint,
+            no_mint,
+            yes_vault,
+            no_vault,
+            collateral_vault,
+            fee_vault: get_ob_fee_vault_pda(order_book),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: rent::id(),
+        })
+        .args(nfl_blockchain::instruction::InitializeOrderBook { capacity: 100, tick_size: 10, lot_size: 10, min_order_size: 10 })
+        .send()
+        .unwrap();
+
+    (order_book, yes_vault, no_vault, collateral_vault)
+}